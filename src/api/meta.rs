@@ -0,0 +1,119 @@
+use alloc::{collections::BTreeMap, string::String, vec, vec::Vec};
+use core::time::Duration;
+
+use crate::io::{Error, ErrorKind};
+
+const CONTENT_TYPE: u8 = 0b01;
+const CREATED: u8 = 0b10;
+
+/// Optional metadata stored alongside the payload of an object.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Meta {
+    pub content_type: Option<String>,
+    /// Creation time as the duration since the unix epoch.
+    pub created: Option<Duration>,
+    pub attributes: BTreeMap<String, String>,
+}
+
+impl Meta {
+    pub fn new() -> Meta {
+        Meta::default()
+    }
+
+    /// Metadata with the creation timestamp set to now.
+    #[cfg(feature = "std")]
+    pub fn now() -> Meta {
+        let created = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default();
+
+        Meta {
+            created: Some(created),
+            ..Meta::default()
+        }
+    }
+
+    pub fn encode(&self) -> Result<Vec<u8>, Error> {
+        let mut flags = 0;
+        let mut buf = vec![0];
+
+        if let Some(content_type) = &self.content_type {
+            flags |= CONTENT_TYPE;
+            encode_str(&mut buf, content_type)?;
+        }
+
+        if let Some(created) = self.created {
+            flags |= CREATED;
+            buf.extend_from_slice(&u64::to_be_bytes(created.as_secs()));
+            buf.extend_from_slice(&u32::to_be_bytes(created.subsec_nanos()));
+        }
+
+        if self.attributes.len() > u16::MAX as usize {
+            return Err(Error::new(ErrorKind::InvalidInput, "Too many attributes."));
+        }
+
+        buf.extend_from_slice(&u16::to_be_bytes(self.attributes.len() as u16));
+        for (key, value) in &self.attributes {
+            encode_str(&mut buf, key)?;
+            encode_str(&mut buf, value)?;
+        }
+
+        buf[0] = flags;
+        Ok(buf)
+    }
+
+    pub fn decode(mut buf: &[u8]) -> Result<Meta, Error> {
+        let flags = take(&mut buf, 1)?[0];
+        let mut meta = Meta::new();
+
+        if flags & CONTENT_TYPE != 0 {
+            meta.content_type = Some(decode_str(&mut buf)?);
+        }
+
+        if flags & CREATED != 0 {
+            let secs = u64::from_be_bytes(take(&mut buf, 8)?.try_into().unwrap());
+            let nanos = u32::from_be_bytes(take(&mut buf, 4)?.try_into().unwrap());
+            if nanos >= 1_000_000_000 {
+                return Err(Error::new(ErrorKind::InvalidData, "Creation timestamp is invalid."));
+            }
+            meta.created = Some(Duration::new(secs, nanos));
+        }
+
+        let len = u16::from_be_bytes(take(&mut buf, 2)?.try_into().unwrap());
+        for _ in 0..len {
+            let key = decode_str(&mut buf)?;
+            let value = decode_str(&mut buf)?;
+            meta.attributes.insert(key, value);
+        }
+
+        if !buf.is_empty() {
+            return Err(Error::new(ErrorKind::InvalidData, "Trailing metadata bytes."));
+        }
+
+        Ok(meta)
+    }
+}
+
+fn encode_str(buf: &mut Vec<u8>, s: &str) -> Result<(), Error> {
+    if s.len() > u16::MAX as usize {
+        return Err(Error::new(ErrorKind::InvalidInput, "Metadata string is too long."));
+    }
+    buf.extend_from_slice(&u16::to_be_bytes(s.len() as u16));
+    buf.extend_from_slice(s.as_bytes());
+    Ok(())
+}
+
+fn decode_str(buf: &mut &[u8]) -> Result<String, Error> {
+    let len = u16::from_be_bytes(take(buf, 2)?.try_into().unwrap()) as usize;
+    String::from_utf8(take(buf, len)?.to_vec())
+        .map_err(|_| Error::new(ErrorKind::InvalidData, "Metadata string is not utf-8."))
+}
+
+fn take<'a>(buf: &mut &'a [u8], n: usize) -> Result<&'a [u8], Error> {
+    if buf.len() < n {
+        return Err(Error::new(ErrorKind::InvalidData, "Metadata is truncated."));
+    }
+    let (head, tail) = buf.split_at(n);
+    *buf = tail;
+    Ok(head)
+}
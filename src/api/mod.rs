@@ -1,4 +1,6 @@
 
+mod meta;
 mod objstr;
 
+pub use meta::Meta;
 pub use objstr::ObjStr;
@@ -1,4 +1,9 @@
-use std::io::{Error, SeekFrom};
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+use crate::io::{Error, ErrorKind, SeekFrom};
+
+use super::Meta;
 
 pub trait ObjStr {
 
@@ -8,8 +13,46 @@ pub trait ObjStr {
     /// Read the next object from the stream.
     fn read(&mut self) -> Result<Vec<u8>, Error>;
 
+    /// Read the next object from the stream into buf, replacing its contents.
+    /// 
+    /// The allocation of buf is reused, so repeated reads do not allocate per object.
+    fn read_into(&mut self, buf: &mut Vec<u8>) -> Result<(), Error> {
+        let data = self.read()?;
+        buf.clear();
+        buf.extend_from_slice(&data);
+        Ok(())
+    }
+
+    /// Read len bytes starting at offset of the payload of the next object, without moving the cursor.
+    /// 
+    /// If the range exceeds the payload, error occurs.
+    fn read_range(&mut self, offset: u64, len: usize) -> Result<Vec<u8>, Error> {
+        let data = self.read()?;
+        self.seek(SeekFrom::Current(-1))?;
+
+        let start = usize::try_from(offset).unwrap_or(usize::MAX);
+        match start.checked_add(len) {
+            Some(end) if end <= data.len() => Ok(data[start..end].to_vec()),
+            _ => Err(Error::new(ErrorKind::InvalidInput, "Range exceeds object length."))
+        }
+    }
+
+    /// Read the metadata of the next object from the stream without loading its payload.
+    /// 
+    /// If the object has no metadata, None is returned.
+    fn read_meta(&mut self) -> Result<Option<Meta>, Error> {
+        self.read().map(|_| None)
+    }
+
+    /// Read the next object together with its metadata from the stream.
+    fn read_with_meta(&mut self) -> Result<(Vec<u8>, Option<Meta>), Error> {
+        Ok((self.read()?, None))
+    }
+
     /// Returns the length of the current object (+ the following objects if objs > 1) and subtracts ops*contents from the length.
     /// 
+    /// The metadata section of an object counts as content.
+    /// 
     /// If contents::0 and objs::0, 0 is returned.
     /// 
     /// If contents::0 and objs::1, the length of the current object is returned.
@@ -38,11 +81,151 @@ pub trait ObjStr {
     /// If objs::1 and data.len() < 1, the current object will be overwritten with multiple objects.
     /// 
     /// If objs::2 and data.len() < 1, the current object and the following objects will be overwritten with multiple objects.
-    fn overwrite(&mut self, data: Vec<Vec<u8>>, objs: u8) -> Result<(), Error>;
+    fn overwrite(&mut self, data: &[&[u8]], objs: u8) -> Result<(), Error>;
+
+    /// Overwrites the payload of the current object starting at offset with data, without moving the cursor.
+    /// 
+    /// The length of the object never changes, if the data exceeds the payload, error occurs.
+    /// 
+    /// The default implementation rewrites the whole object.
+    fn patch(&mut self, offset: u64, data: &[u8]) -> Result<(), Error> {
+        let mut obj = self.read()?;
+        self.seek(SeekFrom::Current(-1))?;
+
+        let start = usize::try_from(offset).unwrap_or(usize::MAX);
+        match start.checked_add(data.len()) {
+            Some(end) if end <= obj.len() => obj[start..end].copy_from_slice(data),
+            _ => return Err(Error::new(ErrorKind::InvalidInput, "Range exceeds object length."))
+        }
+
+        self.overwrite(&[&obj], 1)?;
+        self.seek(SeekFrom::Current(-1))
+    }
 
     /// Appends an object to the end of the stream.
-    fn append(&mut self, data: Vec<u8>) -> Result<(), Error>;
+    /// 
+    /// If the stream format can not hold an object of this length, error occurs and the stream is left untouched.
+    fn append(&mut self, data: &[u8]) -> Result<(), Error>;
+
+    /// Appends an object with metadata to the end of the stream.
+    /// 
+    /// The default implementation fails, for stream formats that can not hold metadata.
+    fn append_with_meta(&mut self, _data: &[u8], _meta: &Meta) -> Result<(), Error> {
+        Err(Error::new(ErrorKind::InvalidInput, "Stream can not hold metadata."))
+    }
 
     /// Deletes the current and all following objects.
     fn cut(&mut self) -> Result<(), Error>;
+
+    /// Deletes the first n objects of the stream.
+    /// 
+    /// If the stream holds fewer than n objects, error occurs and the stream is left untouched.
+    /// 
+    /// The default implementation rewrites all following objects and leaves the cursor at the stream start.
+    fn trim_front(&mut self, n: u64) -> Result<(), Error> {
+        self.seek(SeekFrom::Start(n))?;
+
+        let mut rest = Vec::new();
+        loop {
+            match self.read() {
+                Ok(data) => rest.push(data),
+                Err(e) if e.kind() == ErrorKind::UnexpectedEof => break,
+                Err(e) => return Err(e),
+            }
+        }
+
+        self.seek(SeekFrom::Start(0))?;
+        self.cut()?;
+
+        for data in rest {
+            self.append(&data)?;
+        }
+
+        self.seek(SeekFrom::Start(0))
+    }
+
+    /// Returns the index of the first object for which pred returns false and moves the cursor to it.
+    /// 
+    /// The stream has to be partitioned by pred, all objects for which it returns true come first.
+    /// 
+    /// The default implementation gallops over the object indices and bisects the found range.
+    /// The cursor is moved between the probes with relative seeks, so all probes together walk O(n) objects.
+    /// `FileObjStr` bisects byte offsets instead and reads O(log n) objects.
+    fn partition_point<P>(&mut self, mut pred: P) -> Result<u64, Error>
+    where
+        P: FnMut(&[u8]) -> bool,
+        Self: Sized
+    {
+        self.seek(SeekFrom::Start(0))?;
+        let mut cursor = 0;
+
+        let mut lo = 0;
+        let mut step = 1;
+
+        let mut hi = loop {
+            let probe = lo + step - 1;
+            if probe_at(self, &mut cursor, probe, &mut pred)? == Some(true) {
+                lo = probe + 1;
+                step *= 2;
+            } else {
+                break probe;
+            }
+        };
+
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            if probe_at(self, &mut cursor, mid, &mut pred)? == Some(true) {
+                lo = mid + 1;
+            } else {
+                hi = mid;
+            }
+        }
+
+        self.seek(SeekFrom::Current(lo as i64 - cursor as i64))?;
+        Ok(lo)
+    }
+
+    /// Binary searches a stream sorted by f and moves the cursor to the returned index.
+    /// 
+    /// If a matching object is found, Ok is returned with its index, otherwise Err with the index where it could be inserted.
+    fn binary_search_by<F>(&mut self, mut f: F) -> Result<Result<u64, u64>, Error>
+    where
+        F: FnMut(&[u8]) -> Ordering,
+        Self: Sized
+    {
+        let idx = self.partition_point(|obj| f(obj) == Ordering::Less)?;
+
+        match self.read() {
+            Ok(obj) => {
+                self.seek(SeekFrom::Current(-1))?;
+                if f(&obj) == Ordering::Equal {
+                    Ok(Ok(idx))
+                } else {
+                    Ok(Err(idx))
+                }
+            },
+            Err(e) if e.kind() == ErrorKind::UnexpectedEof => Ok(Err(idx)),
+            Err(e) => Err(e)
+        }
+    }
+}
+
+/// Evaluates pred for the object at idx, None if the stream ends before idx.
+/// 
+/// cursor is the index of the object the cursor of str is at, it is moved relative from there.
+fn probe_at<S: ObjStr, P: FnMut(&[u8]) -> bool>(str: &mut S, cursor: &mut u64, idx: u64, pred: &mut P) -> Result<Option<bool>, Error> {
+    let res = str.seek(SeekFrom::Current(idx as i64 - *cursor as i64)).and_then(|_| str.read());
+
+    match res {
+        Ok(obj) => {
+            *cursor = idx + 1;
+            Ok(Some(pred(&obj)))
+        },
+        Err(e) if e.kind() == ErrorKind::UnexpectedEof => {
+            str.seek(SeekFrom::Start(0))?;
+            *cursor = 0;
+            Ok(None)
+        },
+        Err(e) => Err(e)
+    }
 }
\ No newline at end of file
@@ -0,0 +1,53 @@
+use std::{env, io::Error, net::TcpListener, path::Path, process::ExitCode};
+
+use objstr::{file::FileObjStr, net::Server};
+
+const USAGE: &str = "\
+Usage: objstr-server <addr> <path>
+       objstr-server --http <addr> <dir>
+
+Serves the stream at path to RemoteObjStr clients connecting to addr, e.g. 0.0.0.0:7070,
+or with --http the streams in dir over HTTP if built with the http feature";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    let res = match args.as_slice() {
+        [addr, path] => run(addr, path),
+        ["--http", addr, dir] => run_http(addr, dir),
+        _ => {
+            eprintln!("{}", USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    match res {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("objstr-server: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(addr: &str, path: &str) -> Result<(), Error> {
+    let str = FileObjStr::new(Path::new(path))?;
+    let listener = TcpListener::bind(addr)?;
+
+    println!("serving {} on {}", path, listener.local_addr()?);
+    Server::new(str).serve(listener)
+}
+
+#[cfg(feature = "http")]
+fn run_http(addr: &str, dir: &str) -> Result<(), Error> {
+    let listener = TcpListener::bind(addr)?;
+
+    println!("serving {} over http on {}", dir, listener.local_addr()?);
+    objstr::net::Gateway::new(dir).serve(listener)
+}
+
+#[cfg(not(feature = "http"))]
+fn run_http(_: &str, _: &str) -> Result<(), Error> {
+    Err(Error::new(std::io::ErrorKind::Unsupported, "Built without the http feature."))
+}
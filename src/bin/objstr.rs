@@ -0,0 +1,129 @@
+use std::{env, fs::File, io::{BufReader, BufWriter, Error, ErrorKind, Write as _}, path::Path, process::ExitCode};
+
+use objstr::{convert::{self, Encoding}, diff::{self, Edit}, file::{salvage, FileObjStr}};
+
+const USAGE: &str = "\
+Usage: objstr <command> [args]
+
+Commands:
+    salvage <src> <dest>                       Recover the intact objects of a damaged stream into a new stream
+    export <format> <src> <dest> [--base64]    Export the objects of a stream, which must not carry metadata
+    import <format> <src> <dest>               Append exported objects to a stream
+    diff <a> <b>                               Print the object edits that turn stream a into b
+
+Formats:
+    jsonl    JSON Lines, one object per line, payloads as text or --base64
+    csv      CSV with a header line, one object per record, payloads as text or --base64
+    dir      Directory with one file per object
+    raw      Length-delimited raw dump";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    let res = match args.as_slice() {
+        ["salvage", src, dest] => run_salvage(src, dest),
+        ["export", format, src, dest] => run_export(format, src, dest, Encoding::Utf8),
+        ["export", format, src, dest, "--base64"] => run_export(format, src, dest, Encoding::Base64),
+        ["import", format, src, dest] => run_import(format, src, dest),
+        ["diff", a, b] => run_diff(a, b),
+        _ => {
+            eprintln!("{}", USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    match res {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("objstr: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run_salvage(src: &str, dest: &str) -> Result<(), Error> {
+    let report = salvage(Path::new(src), Path::new(dest))?;
+
+    println!("recovered {} objects", report.recovered);
+    for range in report.lost {
+        println!("lost bytes {}..{}", range.start, range.end);
+    }
+    for range in report.suspicious {
+        println!("suspicious empty objects at bytes {}..{}", range.start, range.end);
+    }
+
+    Ok(())
+}
+
+fn run_export(format: &str, src: &str, dest: &str, encoding: Encoding) -> Result<(), Error> {
+    check_format(format)?;
+    if encoding == Encoding::Base64 && !matches!(format, "jsonl" | "csv") {
+        return Err(Error::new(ErrorKind::InvalidInput, "The --base64 flag only applies to the jsonl and csv formats."));
+    }
+    if !Path::new(src).exists() {
+        return Err(Error::new(ErrorKind::NotFound, "Source stream does not exist."));
+    }
+
+    let mut str = FileObjStr::new(Path::new(src))?;
+
+    let count = if format == "dir" {
+        convert::export_dir(&mut str, Path::new(dest))?
+    } else {
+        let mut out = BufWriter::new(File::create(dest)?);
+        let count = match format {
+            "jsonl" => convert::export_jsonl(&mut str, &mut out, encoding)?,
+            "csv" => convert::export_csv(&mut str, &mut out, encoding)?,
+            _ => convert::export_raw(&mut str, &mut out)?
+        };
+        out.flush()?;
+        count
+    };
+
+    println!("exported {} objects", count);
+    Ok(())
+}
+
+fn run_import(format: &str, src: &str, dest: &str) -> Result<(), Error> {
+    check_format(format)?;
+
+    let mut str = FileObjStr::new(Path::new(dest))?;
+
+    let count = match format {
+        "dir" => convert::import_dir(&mut str, Path::new(src))?,
+        "jsonl" => convert::import_jsonl(&mut str, BufReader::new(File::open(src)?))?,
+        "csv" => convert::import_csv(&mut str, BufReader::new(File::open(src)?))?,
+        _ => convert::import_raw(&mut str, BufReader::new(File::open(src)?))?
+    };
+
+    println!("imported {} objects", count);
+    Ok(())
+}
+
+fn run_diff(a: &str, b: &str) -> Result<(), Error> {
+    for path in [a, b] {
+        if !Path::new(path).exists() {
+            return Err(Error::new(ErrorKind::NotFound, "Stream does not exist."));
+        }
+    }
+
+    let mut str_a = FileObjStr::new(Path::new(a))?;
+    let mut str_b = FileObjStr::new(Path::new(b))?;
+
+    for edit in diff::diff(&mut str_a, &mut str_b)? {
+        match edit {
+            Edit::Insert { index, data } => println!("+ {} ({} bytes)", index, data.len()),
+            Edit::Remove { index } => println!("- {}", index),
+            Edit::Change { index, data } => println!("~ {} ({} bytes)", index, data.len())
+        }
+    }
+
+    Ok(())
+}
+
+fn check_format(format: &str) -> Result<(), Error> {
+    if !matches!(format, "jsonl" | "csv" | "dir" | "raw") {
+        return Err(Error::new(ErrorKind::InvalidInput, "Unknown format, expected jsonl, csv, dir or raw."));
+    }
+    Ok(())
+}
@@ -0,0 +1,52 @@
+use std::io::Error;
+
+use super::invalid;
+
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+pub fn encode(data: &[u8]) -> String {
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+
+    for chunk in data.chunks(3) {
+        let b = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let n = u32::from_be_bytes([0, b[0], b[1], b[2]]);
+
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+
+    out
+}
+
+pub fn decode(s: &str) -> Result<Vec<u8>, Error> {
+    let s = s.as_bytes();
+    if !s.len().is_multiple_of(4) {
+        return Err(invalid("Invalid base64 length."));
+    }
+
+    let mut out = Vec::with_capacity(s.len() / 4 * 3);
+
+    for (i, chunk) in s.chunks(4).enumerate() {
+        let last = i == s.len() / 4 - 1;
+        let pad = chunk.iter().rev().take_while(|&&c| c == b'=').count();
+        if pad > 2 || (pad > 0 && !last) {
+            return Err(invalid("Invalid base64 padding."));
+        }
+
+        let mut n = 0u32;
+        for &c in &chunk[..4 - pad] {
+            let v = ALPHABET.iter().position(|&a| a == c).ok_or_else(|| invalid("Invalid base64 character."))?;
+            n = n << 6 | v as u32;
+        }
+        n <<= 6 * pad as u32;
+
+        out.extend_from_slice(&n.to_be_bytes()[1..4 - pad]);
+    }
+
+    Ok(out)
+}
@@ -0,0 +1,94 @@
+use std::{io::{BufRead, Error, Write}, mem};
+
+use crate::api::ObjStr;
+
+use super::{append_record, for_each_object, invalid, Encoding};
+
+const HEADER: &str = "index,length,encoding,payload";
+
+/// Writes every object as one CSV record with its index, length, encoding and payload.
+/// 
+/// The payload field is always quoted, so it may contain commas and line breaks.
+pub fn export_csv<S: ObjStr>(str: &mut S, out: &mut impl Write, encoding: Encoding) -> Result<u64, Error> {
+    writeln!(out, "{}", HEADER)?;
+
+    for_each_object(str, |index, data| {
+        let (name, payload) = encoding.encode(&data);
+        writeln!(out, "{},{},{},\"{}\"", index, data.len(), name, payload.replace('"', "\"\""))
+    })
+}
+
+/// Appends the objects of CSV records written by `export_csv` in record order.
+/// 
+/// The input is read record by record, so it is never held in memory as a whole.
+pub fn import_csv<S: ObjStr>(str: &mut S, mut input: impl BufRead) -> Result<u64, Error> {
+    let mut line = String::new();
+
+    if next_record(&mut input, &mut line)?.is_none_or(|header| header.join(",") != HEADER) {
+        return Err(invalid("Missing CSV header."));
+    }
+
+    let mut count = 0;
+    while let Some(record) = next_record(&mut input, &mut line)? {
+        let [index, length, encoding, payload] = <[String; 4]>::try_from(record)
+            .map_err(|_| invalid("CSV record must have four fields."))?;
+
+        let index = index.parse().map_err(|_| invalid("Malformed number."))?;
+        let length = length.parse().map_err(|_| invalid("Malformed number."))?;
+        let data = Encoding::decode(&encoding, &payload)?;
+
+        append_record(str, count, index, length, &data)?;
+        count += 1;
+    }
+
+    Ok(count)
+}
+
+/// Reads the fields of the next RFC 4180 record, None at the input end.
+/// 
+/// A quoted field may span lines, line is the buffer they are read into.
+fn next_record(input: &mut impl BufRead, line: &mut String) -> Result<Option<Vec<String>>, Error> {
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut started = false;
+
+    loop {
+        line.clear();
+        if input.read_line(line)? == 0 {
+            break;
+        }
+        started = true;
+
+        let mut chars = line.chars().peekable();
+        while let Some(c) = chars.next() {
+            match (quoted, c) {
+                (true, '"') if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                },
+                (true, '"') => quoted = false,
+                (true, c) => field.push(c),
+                (false, '"') if field.is_empty() => quoted = true,
+                (false, ',') => record.push(mem::take(&mut field)),
+                (false, '\r') if chars.peek() == Some(&'\n') => {},
+                (false, '\n') => {
+                    record.push(field);
+                    return Ok(Some(record));
+                },
+                (false, c) => field.push(c)
+            }
+        }
+    }
+
+    if quoted {
+        return Err(invalid("Unterminated CSV field."));
+    }
+
+    if !started {
+        return Ok(None);
+    }
+
+    record.push(field);
+    Ok(Some(record))
+}
@@ -0,0 +1,49 @@
+use std::{fs, io::{Error, ErrorKind}, path::Path};
+
+use crate::api::ObjStr;
+
+use super::{for_each_object, invalid};
+
+/// Writes every object to its own file named by its index, e.g. `0.bin`, into dir.
+/// 
+/// dir is created if missing. If it is not empty, error occurs, so no object files of an earlier export are left behind.
+pub fn export_dir<S: ObjStr>(str: &mut S, dir: &Path) -> Result<u64, Error> {
+    fs::create_dir_all(dir)?;
+
+    if fs::read_dir(dir)?.next().is_some() {
+        return Err(Error::new(ErrorKind::AlreadyExists, "Export directory is not empty."));
+    }
+
+    for_each_object(str, |index, data| {
+        fs::write(dir.join(format!("{}.bin", index)), data)
+    })
+}
+
+/// Appends the objects of a directory written by `export_dir` in index order.
+/// 
+/// The indices have to be contiguous from 0.
+pub fn import_dir<S: ObjStr>(str: &mut S, dir: &Path) -> Result<u64, Error> {
+    let mut indices = Vec::new();
+
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "bin") {
+            let index: u64 = path.file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| stem.parse().ok())
+                .ok_or_else(|| invalid("Object file name is not an index."))?;
+            indices.push(index);
+        }
+    }
+
+    indices.sort_unstable();
+
+    for (expected, index) in indices.iter().enumerate() {
+        if *index != expected as u64 {
+            return Err(invalid("Object indices are not contiguous."));
+        }
+        str.append(&fs::read(dir.join(format!("{}.bin", index)))?)?;
+    }
+
+    Ok(indices.len() as u64)
+}
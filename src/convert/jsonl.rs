@@ -0,0 +1,185 @@
+use std::io::{BufRead, Error, Write};
+
+use crate::api::ObjStr;
+
+use super::{append_record, for_each_object, invalid, Encoding};
+
+/// Writes every object as one JSON line with its index, length, encoding and payload.
+pub fn export_jsonl<S: ObjStr>(str: &mut S, out: &mut impl Write, encoding: Encoding) -> Result<u64, Error> {
+    for_each_object(str, |index, data| write_jsonl_record(out, index, &data, encoding))
+}
+
+/// Writes a single JSON line as `export_jsonl` does.
+pub(crate) fn write_jsonl_record(out: &mut impl Write, index: u64, data: &[u8], encoding: Encoding) -> Result<(), Error> {
+    let (name, payload) = encoding.encode(data);
+    writeln!(
+        out,
+        "{{\"index\":{},\"length\":{},\"encoding\":\"{}\",\"payload\":\"{}\"}}",
+        index,
+        data.len(),
+        name,
+        escape(&payload)
+    )
+}
+
+/// Appends the objects of JSON lines written by `export_jsonl` in line order.
+pub fn import_jsonl<S: ObjStr>(str: &mut S, input: impl BufRead) -> Result<u64, Error> {
+    let mut count = 0;
+
+    for line in input.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let record = Record::parse(&line)?;
+        let data = Encoding::decode(&record.encoding, &record.payload)?;
+        append_record(str, count, record.index, record.length, &data)?;
+        count += 1;
+    }
+
+    Ok(count)
+}
+
+#[derive(Default)]
+struct Record {
+    index: u64,
+    length: u64,
+    encoding: String,
+    payload: String
+}
+
+impl Record {
+    /// Parses a flat JSON object with the record fields.
+    fn parse(line: &str) -> Result<Record, Error> {
+        let mut record = Record::default();
+        let mut chars = line.trim().chars().peekable();
+
+        expect(&mut chars, '{')?;
+        skip_ws(&mut chars);
+
+        if chars.peek() == Some(&'}') {
+            return Err(invalid("Record is empty."));
+        }
+
+        loop {
+            skip_ws(&mut chars);
+            let key = parse_string(&mut chars)?;
+            skip_ws(&mut chars);
+            expect(&mut chars, ':')?;
+            skip_ws(&mut chars);
+
+            match key.as_str() {
+                "index" => record.index = parse_number(&mut chars)?,
+                "length" => record.length = parse_number(&mut chars)?,
+                "encoding" => record.encoding = parse_string(&mut chars)?,
+                "payload" => record.payload = parse_string(&mut chars)?,
+                _ => return Err(invalid("Unknown record field."))
+            }
+
+            skip_ws(&mut chars);
+            match chars.next() {
+                Some(',') => continue,
+                Some('}') => break,
+                _ => return Err(invalid("Malformed record."))
+            }
+        }
+
+        skip_ws(&mut chars);
+        if chars.next().is_some() {
+            return Err(invalid("Trailing characters after record."));
+        }
+
+        Ok(record)
+    }
+}
+
+type Chars<'a> = std::iter::Peekable<std::str::Chars<'a>>;
+
+fn skip_ws(chars: &mut Chars) {
+    while chars.peek().is_some_and(|c| c.is_whitespace()) {
+        chars.next();
+    }
+}
+
+fn expect(chars: &mut Chars, c: char) -> Result<(), Error> {
+    if chars.next() != Some(c) {
+        return Err(invalid("Malformed record."));
+    }
+    Ok(())
+}
+
+fn parse_number(chars: &mut Chars) -> Result<u64, Error> {
+    let mut digits = String::new();
+    while let Some(c) = chars.peek().filter(|c| c.is_ascii_digit()) {
+        digits.push(*c);
+        chars.next();
+    }
+    digits.parse().map_err(|_| invalid("Malformed number."))
+}
+
+fn parse_string(chars: &mut Chars) -> Result<String, Error> {
+    expect(chars, '"')?;
+
+    let mut s = String::new();
+    loop {
+        match chars.next().ok_or_else(|| invalid("Unterminated string."))? {
+            '"' => return Ok(s),
+            '\\' => match chars.next().ok_or_else(|| invalid("Unterminated string."))? {
+                '"' => s.push('"'),
+                '\\' => s.push('\\'),
+                '/' => s.push('/'),
+                'b' => s.push('\u{8}'),
+                'f' => s.push('\u{c}'),
+                'n' => s.push('\n'),
+                'r' => s.push('\r'),
+                't' => s.push('\t'),
+                'u' => s.push(parse_unicode(chars)?),
+                _ => return Err(invalid("Invalid escape sequence."))
+            },
+            c => s.push(c)
+        }
+    }
+}
+
+fn parse_unicode(chars: &mut Chars) -> Result<char, Error> {
+    let high = parse_hex(chars)?;
+
+    if !(0xd800..0xdc00).contains(&high) {
+        return char::from_u32(high).ok_or_else(|| invalid("Invalid unicode escape."));
+    }
+
+    expect(chars, '\\')?;
+    expect(chars, 'u')?;
+    let low = parse_hex(chars)?;
+
+    if !(0xdc00..0xe000).contains(&low) {
+        return Err(invalid("Invalid unicode escape."));
+    }
+
+    char::from_u32(0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00)).ok_or_else(|| invalid("Invalid unicode escape."))
+}
+
+fn parse_hex(chars: &mut Chars) -> Result<u32, Error> {
+    let hex: String = chars.take(4).collect();
+    if hex.len() != 4 {
+        return Err(invalid("Invalid unicode escape."));
+    }
+    u32::from_str_radix(&hex, 16).map_err(|_| invalid("Invalid unicode escape."))
+}
+
+fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c)
+        }
+    }
+    out
+}
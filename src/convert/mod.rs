@@ -0,0 +1,80 @@
+
+mod base64;
+mod csv;
+mod dir;
+mod jsonl;
+mod raw;
+
+use std::io::{Error, ErrorKind, SeekFrom};
+
+use crate::api::ObjStr;
+
+pub use csv::{export_csv, import_csv};
+pub use dir::{export_dir, import_dir};
+pub use jsonl::{export_jsonl, import_jsonl};
+#[cfg(feature = "http")]
+pub(crate) use jsonl::write_jsonl_record;
+pub use raw::{export_raw, import_raw};
+
+/// How payloads are written to text formats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    /// Payloads are written as UTF-8 text, payloads that are not valid UTF-8 fall back to base64.
+    Utf8,
+    /// Payloads are always written as base64.
+    Base64
+}
+
+impl Encoding {
+    fn encode(self, data: &[u8]) -> (&'static str, String) {
+        match (self, std::str::from_utf8(data)) {
+            (Encoding::Utf8, Ok(s)) => ("utf8", s.to_string()),
+            _ => ("base64", base64::encode(data))
+        }
+    }
+
+    fn decode(name: &str, payload: &str) -> Result<Vec<u8>, Error> {
+        match name {
+            "utf8" => Ok(payload.as_bytes().to_vec()),
+            "base64" => base64::decode(payload),
+            _ => Err(invalid("Unknown payload encoding."))
+        }
+    }
+}
+
+/// Calls f with the index and payload of every object, starting at the stream start.
+/// 
+/// None of the formats holds object metadata, so an object with metadata is rejected
+/// instead of being exported without it. Objects before it have already been passed to f.
+fn for_each_object<S: ObjStr>(str: &mut S, mut f: impl FnMut(u64, Vec<u8>) -> Result<(), Error>) -> Result<u64, Error> {
+    str.seek(SeekFrom::Start(0))?;
+
+    let mut index = 0;
+    loop {
+        let data = match str.read_with_meta() {
+            Ok((_, Some(_))) => return Err(Error::new(ErrorKind::InvalidInput, "Object metadata can not be exported.")),
+            Ok((data, None)) => data,
+            Err(e) if e.kind() == ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(e)
+        };
+        f(index, data)?;
+        index += 1;
+    }
+
+    Ok(index)
+}
+
+/// Checks an imported record against its index and length fields and appends it.
+fn append_record<S: ObjStr>(str: &mut S, expected: u64, index: u64, length: u64, data: &[u8]) -> Result<(), Error> {
+    if index != expected {
+        return Err(invalid("Record index is out of order."));
+    }
+    if length != data.len() as u64 {
+        return Err(invalid("Record length does not match its payload."));
+    }
+    str.append(data)
+}
+
+fn invalid(msg: &'static str) -> Error {
+    Error::new(ErrorKind::InvalidData, msg)
+}
@@ -0,0 +1,56 @@
+use std::io::{Error, ErrorKind, Read, Write};
+
+use crate::api::ObjStr;
+
+use super::{for_each_object, invalid};
+
+/// Writes every object as a big-endian u32 length followed by its payload.
+pub fn export_raw<S: ObjStr>(str: &mut S, out: &mut impl Write) -> Result<u64, Error> {
+    for_each_object(str, |_, data| {
+        let len = u32::try_from(data.len()).map_err(|_| Error::new(ErrorKind::InvalidInput, "Data length is too long."))?;
+        out.write_all(&u32::to_be_bytes(len))?;
+        out.write_all(&data)
+    })
+}
+
+/// Appends the objects of a dump written by `export_raw` in dump order.
+pub fn import_raw<S: ObjStr>(str: &mut S, mut input: impl Read) -> Result<u64, Error> {
+    let mut count = 0;
+    let mut data = Vec::new();
+
+    loop {
+        let mut len_buf = [0; 4];
+        let n = read_full(&mut input, &mut len_buf)?;
+
+        if n == 0 {
+            break;
+        }
+        if n < len_buf.len() {
+            return Err(invalid("Truncated length in raw dump."));
+        }
+
+        let len = u32::from_be_bytes(len_buf) as u64;
+        data.clear();
+        if (&mut input).take(len).read_to_end(&mut data)? as u64 != len {
+            return Err(invalid("Truncated payload in raw dump."));
+        }
+
+        str.append(&data)?;
+        count += 1;
+    }
+
+    Ok(count)
+}
+
+fn read_full(input: &mut impl Read, buf: &mut [u8]) -> Result<usize, Error> {
+    let mut n = 0;
+    while n < buf.len() {
+        match input.read(&mut buf[n..]) {
+            Ok(0) => break,
+            Ok(res) => n += res,
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e)
+        }
+    }
+    Ok(n)
+}
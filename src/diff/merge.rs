@@ -0,0 +1,73 @@
+use std::{cmp::Ordering, io::{Error, ErrorKind, SeekFrom}};
+
+use crate::api::ObjStr;
+
+/// Merges streams that are each sorted by cmp into dest and returns the number of merged objects.
+///
+/// The sources are read from their start. Objects that compare equal are taken in source order,
+/// so the merge is stable.
+pub fn merge<S, D, F>(sources: &mut [S], dest: &mut D, mut cmp: F) -> Result<u64, Error>
+where
+    S: ObjStr,
+    D: ObjStr,
+    F: FnMut(&[u8], &[u8]) -> Ordering
+{
+    let mut heads = Vec::with_capacity(sources.len());
+    for (i, source) in sources.iter_mut().enumerate() {
+        source.seek(SeekFrom::Start(0))?;
+        if let Some(data) = next(source)? {
+            heads.push((data, i));
+        }
+    }
+
+    for i in (0..heads.len() / 2).rev() {
+        sift_down(&mut heads, i, &mut cmp);
+    }
+
+    let mut count = 0;
+    while !heads.is_empty() {
+        let source = heads[0].1;
+        dest.append(&heads[0].0)?;
+        count += 1;
+
+        match next(&mut sources[source])? {
+            Some(data) => {
+                if cmp(&heads[0].0, &data) == Ordering::Greater {
+                    return Err(Error::new(ErrorKind::InvalidData, "Source stream is not sorted."));
+                }
+                heads[0].0 = data;
+            },
+            None => {
+                heads.swap_remove(0);
+            }
+        }
+        sift_down(&mut heads, 0, &mut cmp);
+    }
+
+    Ok(count)
+}
+
+fn next<S: ObjStr>(str: &mut S) -> Result<Option<Vec<u8>>, Error> {
+    match str.read() {
+        Ok(data) => Ok(Some(data)),
+        Err(e) if e.kind() == ErrorKind::UnexpectedEof => Ok(None),
+        Err(e) => Err(e)
+    }
+}
+
+/// Restores the min-heap order below i, heads are ordered by cmp and then by source index.
+fn sift_down<F: FnMut(&[u8], &[u8]) -> Ordering>(heap: &mut [(Vec<u8>, usize)], mut i: usize, cmp: &mut F) {
+    loop {
+        let mut min = i;
+        for child in [2 * i + 1, 2 * i + 2] {
+            if child < heap.len() && cmp(&heap[child].0, &heap[min].0).then(heap[child].1.cmp(&heap[min].1)).is_lt() {
+                min = child;
+            }
+        }
+        if min == i {
+            return;
+        }
+        heap.swap(i, min);
+        i = min;
+    }
+}
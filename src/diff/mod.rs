@@ -0,0 +1,6 @@
+
+mod merge;
+mod script;
+
+pub use merge::merge;
+pub use script::{diff, patch, Edit};
@@ -0,0 +1,257 @@
+use std::{hash::{DefaultHasher, Hash as _, Hasher as _}, io::{Error, ErrorKind, SeekFrom}};
+
+use crate::api::ObjStr;
+
+/// Object-level change of an edit script, indices refer to objects of the original stream.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Edit {
+    /// The object is inserted before the object at index, at the end if index is the object count.
+    Insert { index: u64, data: Vec<u8> },
+    /// The object at index is removed.
+    Remove { index: u64 },
+    /// The object at index is replaced.
+    Change { index: u64, data: Vec<u8> }
+}
+
+impl Edit {
+    pub fn index(&self) -> u64 {
+        match self {
+            Edit::Insert { index, .. } | Edit::Remove { index } | Edit::Change { index, .. } => *index
+        }
+    }
+}
+
+/// Returns the shortest edit script that turns a into b.
+///
+/// The objects are aligned by their hashes with the Myers algorithm, so each stream is read twice
+/// and only the hashes are kept in memory. Aligned objects are compared byte by byte on the second pass.
+pub fn diff<A: ObjStr, B: ObjStr>(a: &mut A, b: &mut B) -> Result<Vec<Edit>, Error> {
+    let hashes_a = hashes(a)?;
+    let hashes_b = hashes(b)?;
+
+    let mut pairs = align(&hashes_a, &hashes_b);
+    pairs.push((hashes_a.len(), hashes_b.len()));
+
+    a.seek(SeekFrom::Start(0))?;
+    b.seek(SeekFrom::Start(0))?;
+
+    let mut edits = Vec::new();
+    let (mut i, mut j) = (0, 0);
+
+    for (x, y) in pairs {
+        while i < x && j < y {
+            a.read()?;
+            edits.push(Edit::Change { index: i as u64, data: b.read()? });
+            i += 1;
+            j += 1;
+        }
+        while i < x {
+            a.read()?;
+            edits.push(Edit::Remove { index: i as u64 });
+            i += 1;
+        }
+        while j < y {
+            edits.push(Edit::Insert { index: i as u64, data: b.read()? });
+            j += 1;
+        }
+
+        if i < hashes_a.len() && j < hashes_b.len() {
+            let data = b.read()?;
+            if a.read()? != data {
+                edits.push(Edit::Change { index: i as u64, data });
+            }
+            i += 1;
+            j += 1;
+        }
+    }
+
+    Ok(edits)
+}
+
+/// Applies an edit script produced by `diff` to str.
+///
+/// The edits have to be ordered by index. The objects from the first edited index on are rewritten,
+/// the cursor is left at the stream start. Kept objects are rewritten with their metadata,
+/// inserted and changed objects have none.
+pub fn patch<S: ObjStr>(str: &mut S, edits: &[Edit]) -> Result<(), Error> {
+    let Some(first) = edits.first().map(Edit::index) else {
+        return Ok(());
+    };
+
+    if edits.windows(2).any(|w| w[0].index() > w[1].index()) {
+        return Err(Error::new(ErrorKind::InvalidInput, "Edits are not ordered by index."));
+    }
+
+    str.seek(SeekFrom::Start(first))
+        .map_err(|_| Error::new(ErrorKind::InvalidInput, "Edit index exceeds stream."))?;
+
+    let mut rest = Vec::new();
+    loop {
+        match str.read_with_meta() {
+            Ok(obj) => rest.push(Some(obj)),
+            Err(e) if e.kind() == ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(e)
+        }
+    }
+
+    let mut tail = Vec::new();
+    let mut next = 0;
+
+    for edit in edits {
+        let at = (edit.index() - first) as usize;
+        if at > rest.len() || (at == rest.len() && !matches!(edit, Edit::Insert { .. })) {
+            return Err(Error::new(ErrorKind::InvalidInput, "Edit index exceeds stream."));
+        }
+
+        while next < at {
+            tail.extend(rest[next].take());
+            next += 1;
+        }
+
+        match edit {
+            Edit::Insert { data, .. } => tail.push((data.clone(), None)),
+            Edit::Remove { .. } => {
+                if rest[at].take().is_none() {
+                    return Err(Error::new(ErrorKind::InvalidInput, "Object is edited twice."));
+                }
+                next = at + 1;
+            },
+            Edit::Change { data, .. } => {
+                if rest[at].take().is_none() {
+                    return Err(Error::new(ErrorKind::InvalidInput, "Object is edited twice."));
+                }
+                tail.push((data.clone(), None));
+                next = at + 1;
+            }
+        }
+    }
+
+    tail.extend(rest.into_iter().skip(next).flatten());
+
+    str.seek(SeekFrom::Start(first))?;
+    str.cut()?;
+    for (data, meta) in tail {
+        match meta {
+            Some(meta) => str.append_with_meta(&data, &meta)?,
+            None => str.append(&data)?
+        }
+    }
+
+    str.seek(SeekFrom::Start(0))
+}
+
+fn hashes<S: ObjStr>(str: &mut S) -> Result<Vec<u64>, Error> {
+    str.seek(SeekFrom::Start(0))?;
+
+    let mut hashes = Vec::new();
+    let mut buf = Vec::new();
+    loop {
+        match str.read_into(&mut buf) {
+            Ok(()) => {
+                let mut hasher = DefaultHasher::new();
+                buf.hash(&mut hasher);
+                hashes.push(hasher.finish());
+            },
+            Err(e) if e.kind() == ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(e)
+        }
+    }
+
+    Ok(hashes)
+}
+
+/// Returns the index pairs of a longest common subsequence of a and b, found with the linear space Myers algorithm.
+///
+/// The problem is split at the middle snake of an optimal path and both halves are solved recursively,
+/// so the memory besides the pairs is linear in the stream lengths.
+fn align(a: &[u64], b: &[u64]) -> Vec<(usize, usize)> {
+    let mut pairs = Vec::new();
+    align_into(a, b, 0, 0, &mut pairs);
+    pairs
+}
+
+/// Pushes the pairs of a longest common subsequence of a and b, which start at a0 and b0 of the streams.
+fn align_into(a: &[u64], b: &[u64], a0: usize, b0: usize, pairs: &mut Vec<(usize, usize)>) {
+    let prefix = a.iter().zip(b).take_while(|(x, y)| x == y).count();
+    pairs.extend((0..prefix).map(|i| (a0 + i, b0 + i)));
+
+    let (a, b) = (&a[prefix..], &b[prefix..]);
+    let (a0, b0) = (a0 + prefix, b0 + prefix);
+
+    let suffix = a.iter().rev().zip(b.iter().rev()).take_while(|(x, y)| x == y).count();
+    let (n, m) = (a.len() - suffix, b.len() - suffix);
+
+    // Without a common prefix or suffix, both sides are at least two edits apart, so both halves are smaller.
+    if n > 0 && m > 0 {
+        let (x0, y0, x1, y1) = middle_snake(&a[..n], &b[..m]);
+        align_into(&a[..x0], &b[..y0], a0, b0, pairs);
+        pairs.extend((0..x1 - x0).map(|i| (a0 + x0 + i, b0 + y0 + i)));
+        align_into(&a[x1..n], &b[y1..m], a0 + x1, b0 + y1, pairs);
+    }
+
+    pairs.extend((0..suffix).map(|i| (a0 + n + i, b0 + m + i)));
+}
+
+/// Returns the start and end of the middle snake of a shortest edit path from a to b.
+///
+/// The path is searched from both ends at once until the searches overlap, keeping only the furthest
+/// reaching x of every diagonal.
+fn middle_snake(a: &[u64], b: &[u64]) -> (usize, usize, usize, usize) {
+    let (n, m) = (a.len() as isize, b.len() as isize);
+    let max = (n + m + 1) / 2;
+    let offset = max + 1;
+    let delta = n - m;
+    let odd = delta % 2 != 0;
+
+    // Forward furthest x per diagonal k = x - y, backward furthest distance from the ends per reversed diagonal.
+    let mut forward = vec![0isize; 2 * max as usize + 3];
+    let mut backward = vec![0isize; 2 * max as usize + 3];
+
+    for d in 0..=max {
+        for k in (-d..=d).step_by(2) {
+            let i = (k + offset) as usize;
+            let mut x = if k == -d || (k != d && forward[i - 1] < forward[i + 1]) {
+                forward[i + 1]
+            } else {
+                forward[i - 1] + 1
+            };
+            let mut y = x - k;
+            let (x0, y0) = (x, y);
+
+            while x < n && y < m && a[x as usize] == b[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            forward[i] = x;
+
+            let r = delta - k;
+            if odd && r.abs() < d && x + backward[(r + offset) as usize] >= n {
+                return (x0 as usize, y0 as usize, x as usize, y as usize);
+            }
+        }
+
+        for r in (-d..=d).step_by(2) {
+            let i = (r + offset) as usize;
+            let mut x = if r == -d || (r != d && backward[i - 1] < backward[i + 1]) {
+                backward[i + 1]
+            } else {
+                backward[i - 1] + 1
+            };
+            let mut y = x - r;
+            let (x0, y0) = (x, y);
+
+            while x < n && y < m && a[(n - 1 - x) as usize] == b[(m - 1 - y) as usize] {
+                x += 1;
+                y += 1;
+            }
+            backward[i] = x;
+
+            let k = delta - r;
+            if !odd && k.abs() <= d && forward[(k + offset) as usize] + x >= n {
+                return ((n - x) as usize, (m - y) as usize, (n - x0) as usize, (m - y0) as usize);
+            }
+        }
+    }
+
+    unreachable!("An edit path is at most n + m long.")
+}
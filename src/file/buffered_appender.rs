@@ -0,0 +1,50 @@
+use std::io::Error;
+
+use super::{helpers::encode_frame, FileObjStr};
+
+/// Collects appended objects in memory and writes them to the stream in batches.
+/// 
+/// Objects are only visible in the stream after a flush, which happens once the buffer
+/// holds at least capacity bytes, on an explicit call to `flush` or when the appender is dropped.
+pub struct BufferedAppender<'a> {
+    str: &'a mut FileObjStr,
+    buf: Vec<u8>,
+    n: u64,
+    capacity: usize
+}
+
+impl<'a> BufferedAppender<'a> {
+    pub(crate) fn new(str: &'a mut FileObjStr, capacity: usize) -> BufferedAppender<'a> {
+        BufferedAppender {
+            str,
+            buf: Vec::with_capacity(capacity),
+            n: 0,
+            capacity
+        }
+    }
+
+    /// Buffers an object and flushes if the buffer is full.
+    pub fn append(&mut self, data: impl AsRef<[u8]>) -> Result<(), Error> {
+        encode_frame(&mut self.buf, data.as_ref(), false)?;
+        self.n += 1;
+
+        if self.buf.len() >= self.capacity {
+            self.flush()?;
+        }
+
+        Ok(())
+    }
+
+    /// Appends all buffered objects to the stream.
+    pub fn flush(&mut self) -> Result<(), Error> {
+        let buf = std::mem::replace(&mut self.buf, Vec::with_capacity(self.capacity));
+        let n = std::mem::take(&mut self.n);
+        self.str.append_frames(buf, n)
+    }
+}
+
+impl Drop for BufferedAppender<'_> {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
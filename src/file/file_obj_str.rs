@@ -1,11 +1,19 @@
-use std::{fs::{File, OpenOptions}, io::{Error, ErrorKind, SeekFrom}, path::Path};
+use std::{fs::{self, File, OpenOptions}, io::{self, Error, ErrorKind, Read as _, Seek as _, SeekFrom}, path::{Path, PathBuf}, sync::{atomic::{AtomicU64, Ordering}, mpsc::{self, Receiver}, Arc}, time::SystemTime};
 
-use crate::api::ObjStr;
+use crate::{api::{Meta, ObjStr}, frame::{stream, Storage}};
 
-use super::helpers::{cut, inner_len, jump_stream_end, jump_stream_start, len_calc, read, seek_backward_n, seek_forward, seek_forward_n, truncate, write, write_empty_byte};
+use super::{buffered_appender::BufferedAppender, follow::Follow, helpers::{count, cut, encode_frame, index_of, jump, jump_stream_end, jump_stream_start, patch, position, read, read_into, read_meta, read_range, read_with_meta, seek_backward_n, seek_forward, seek_forward_n, shift_front, stream_len, sync_frame, truncate, walk_index, write, write_empty_byte, write_raw, write_with_meta, EMPTY, OP_LEN}, observer::{Event, Observers}, read_ahead::ReadAhead, retention::Retention, snapshot::Snapshot};
 
 pub struct FileObjStr {
-    file: File
+    file: File,
+    path: PathBuf,
+    count: Option<u64>,
+    retention: Option<Retention>,
+    read_ahead: Option<ReadAhead>,
+    observers: Observers,
+    generation: Arc<AtomicU64>,
+    /// Byte offset and object index of the frame observers last saw and the generation they were taken in.
+    index: Option<(u64, u64, u64)>
 }
 
 impl FileObjStr {
@@ -22,21 +30,526 @@ impl FileObjStr {
                 .read(true)
                 .write(true)
                 .create(true)
+                .truncate(false)
                 .open(path)?;
             truncate(&mut file);
         }     
 
         Ok(
             FileObjStr {
-                file
+                file,
+                path: path.to_path_buf(),
+                count: None,
+                retention: None,
+                read_ahead: None,
+                observers: Observers::default(),
+                generation: Arc::default(),
+                index: None
             }
         )
     }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the number of objects in the stream.
+    pub fn count(&mut self) -> u64 {
+        if let Some(count) = self.count {
+            return count;
+        }
+        self.sync();
+        let count = count(&mut self.file);
+        self.count = Some(count);
+        count
+    }
+
+    /// Appends all objects to the end of the stream with a single write.
+    pub fn append_batch<T: AsRef<[u8]>>(&mut self, data: impl IntoIterator<Item = T>) -> Result<(), Error> {
+        let mut buf = Vec::new();
+        let mut n = 0;
+
+        for d in data {
+            encode_frame(&mut buf, d.as_ref(), false)?;
+            n += 1;
+        }
+
+        self.append_frames(buf, n)
+    }
+
+    /// Returns an appender that buffers objects and appends them in batches of at least capacity bytes.
+    /// 
+    /// The remaining objects are appended when the appender is flushed or dropped.
+    pub fn buffered_appender(&mut self, capacity: usize) -> BufferedAppender<'_> {
+        BufferedAppender::new(self, capacity)
+    }
+
+    pub(crate) fn append_frames(&mut self, mut buf: Vec<u8>, n: u64) -> Result<(), Error> {
+        self.sync();
+        if n == 0 {
+            return Ok(());
+        }
+
+        let lens = if self.observers.is_empty() {
+            vec![0; n as usize]
+        } else {
+            let end = buf.len() as u64;
+            frame_lens(&mut buf, 0, end)?
+        };
+
+        buf.extend_from_slice(&EMPTY);
+
+        jump_stream_end(&mut self.file);
+        write_raw(&mut self.file, &buf);
+        self.appended(&lens)
+    }
+
+    /// Returns a read-only view of the objects currently in the stream.
+    /// 
+    /// The snapshot keeps working while objects are appended, but fails once its objects are cut or modified.
+    pub fn snapshot(&mut self) -> Result<Snapshot, Error> {
+        let count = self.count();
+        let end = stream_len(&mut self.file);
+        Snapshot::new(&self.path, end, count, self.generation.clone())
+    }
+
+    /// Returns an iterator over the objects from the cursor on that waits for objects appended later, like `tail -f`.
+    /// 
+    /// The iterator reads through its own handle, so it also sees objects appended by other processes.
+    /// It fails once objects are cut or modified through this stream.
+    pub fn follow(&mut self) -> Result<Follow, Error> {
+        self.sync();
+        let pos = position(&mut self.file).min(stream_len(&mut self.file));
+        Follow::new(&self.path, pos, self.generation.clone())
+    }
+
+    /// Enables reading ahead in blocks of capacity bytes, which speeds up sequential reads of small objects.
+    /// 
+    /// None disables reading ahead.
+    pub fn set_read_ahead(&mut self, capacity: Option<usize>) {
+        self.sync();
+        self.read_ahead = capacity.map(ReadAhead::new);
+    }
+
+    /// Returns the number of in-place modifications so far, byte offsets taken before the last one may be stale.
+    pub(crate) fn generation(&self) -> u64 {
+        self.generation.load(Ordering::SeqCst)
+    }
+
+    /// Marks that objects already in the stream were modified or removed, which invalidates the snapshots.
+    fn modified(&mut self) {
+        self.generation.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// Moves the file cursor back to the stream cursor and drops the read ahead buffer.
+    fn sync(&mut self) {
+        if let Some(read_ahead) = self.read_ahead.as_mut() {
+            read_ahead.sync(&mut self.file);
+        }
+    }
+
+    /// Returns the byte offset of the cursor in the file.
+    pub fn byte_position(&mut self) -> u64 {
+        self.sync();
+        position(&mut self.file)
+    }
+
+    /// Returns the payload length of the next object without reading it, its metadata does not count.
+    #[cfg(feature = "http")]
+    pub(crate) fn payload_len(&mut self) -> Result<u64, Error> {
+        self.sync();
+        let pos = position(&mut self.file);
+        Ok(stream::payload_span(&mut self.file, pos)?.1)
+    }
+
+    /// Moves the cursor to the frame starting at byte offset pos.
+    pub(crate) fn jump_to(&mut self, pos: u64) {
+        self.sync();
+        jump(&mut self.file, pos);
+    }
+
+    /// Moves the cursor to the first valid frame at or after byte_offset and returns its byte offset.
+    /// 
+    /// A frame is valid if its mirrored lengths agree, its metadata section fits and its neighbours are frames or the stream bounds.
+    /// If no frame follows, the cursor is moved to the stream end and None is returned.
+    pub fn sync_to_frame(&mut self, byte_offset: u64) -> Result<Option<u64>, Error> {
+        self.sync();
+
+        let end = stream_len(&mut self.file);
+        let frame = sync_frame(&mut self.file, byte_offset, end)?;
+
+        jump(&mut self.file, frame.unwrap_or(end));
+        Ok(frame)
+    }
+
+    /// Splits the stream into byte ranges on frame boundaries for a parallel scan.
+    /// 
+    /// The stream is split into four ranges per rayon thread.
+    #[cfg(feature = "rayon")]
+    pub fn par_iter(&mut self) -> Result<super::ParIter, Error> {
+        self.par_iter_with(rayon::current_num_threads() * 4)
+    }
+
+    /// Splits the stream into at most splits byte ranges on frame boundaries for a parallel scan.
+    /// 
+    /// The boundaries are found with `sync_to_frame`. If a payload embeds valid frames, a boundary can land inside it,
+    /// the range before it then fails with InvalidData instead of yielding the embedded frames as objects.
+    #[cfg(feature = "rayon")]
+    pub fn par_iter_with(&mut self, splits: usize) -> Result<super::ParIter, Error> {
+        self.sync();
+
+        let end = stream_len(&mut self.file);
+        let splits = splits.max(1) as u64;
+
+        let mut bounds = vec![0];
+        for i in 1..splits {
+            let target = end * i / splits;
+            if let Some(frame) = sync_frame(&mut self.file, target, end)? {
+                if frame > *bounds.last().unwrap() {
+                    bounds.push(frame);
+                }
+            }
+        }
+        bounds.push(end);
+
+        let ranges = bounds.windows(2).map(|w| (w[0], w[1])).collect();
+        Ok(super::ParIter::new(self.path.clone(), ranges))
+    }
+
+    /// Sets the retention that is enforced after every append.
+    pub fn set_retention(&mut self, retention: Option<Retention>) {
+        self.retention = retention;
+    }
+
+    /// Calls f with every change of the stream made through this handle.
+    pub fn subscribe(&mut self, f: impl FnMut(&Event) + Send + 'static) {
+        self.observers.subscribe(f);
+    }
+
+    /// Returns a receiver of every change of the stream made through this handle.
+    /// 
+    /// Dropping the receiver unsubscribes it.
+    pub fn events(&mut self) -> Receiver<Event> {
+        let (sender, receiver) = mpsc::channel();
+        self.observers.channel(sender);
+        receiver
+    }
+
+    /// Index of the object at the cursor, only computed if anyone observes the stream.
+    /// 
+    /// The index is walked from the frame observers last saw, unless the stream was modified in place since.
+    fn observed_index(&mut self) -> Result<Option<u64>, Error> {
+        if self.observers.is_empty() {
+            return Ok(None);
+        }
+        let pos = position(&mut self.file);
+        let index = match self.index {
+            Some((frame, index, generation)) if generation == self.generation() => walk_index(&mut self.file, frame, index, pos),
+            _ => index_of(&mut self.file, pos)
+        };
+        jump(&mut self.file, pos);
+        self.observed_at(pos, index.as_ref().ok().copied());
+        index.map(Some)
+    }
+
+    /// Index of the frame at pos, walked from the frame observers last saw, the stream start or the stream end, whichever is nearest.
+    fn index_at(&mut self, pos: u64) -> Result<u64, Error> {
+        let mut from = (0, 0);
+        if let Some((frame, index, generation)) = self.index {
+            if generation == self.generation() && frame.abs_diff(pos) < pos {
+                from = (frame, index);
+            }
+        }
+        if let Some(count) = self.count {
+            let end = stream_len(&mut self.file);
+            if end - pos < from.0.abs_diff(pos) {
+                from = (end, count);
+            }
+        }
+        walk_index(&mut self.file, from.0, from.1, pos)
+    }
+
+    /// Remembers that the frame at pos has index, after the stream was modified.
+    fn observed_at(&mut self, pos: u64, index: Option<u64>) {
+        self.index = index.map(|index| (pos, index, self.generation()));
+    }
+
+    fn appended(&mut self, lens: &[u64]) -> Result<(), Error> {
+        let n = lens.len() as u64;
+        if let Some(count) = self.count.as_mut() {
+            *count += n;
+        }
+
+        if !self.observers.is_empty() {
+            let first = self.count() - n;
+            for (i, len) in lens.iter().enumerate() {
+                self.observers.emit(Event::Append { index: first + i as u64, len: *len });
+            }
+        }
+
+        if let Some(retention) = self.retention.take() {
+            let res = self.retain(&retention);
+            self.retention = Some(retention);
+            res?;
+        }
+
+        Ok(())
+    }
+
+    /// Trims expired objects from the front of the stream and returns how many were dropped.
+    /// 
+    /// Nothing is trimmed until one of the limits is exceeded by more than the retention slack.
+    pub fn retain(&mut self, retention: &Retention) -> Result<u64, Error> {
+        self.sync();
+        let now = SystemTime::now();
+        let count = self.count();
+        let bytes = stream_len(&mut self.file);
+        let pos = position(&mut self.file);
+
+        let mut over = retention.over_objects(count, true) || retention.over_bytes(bytes, true);
+
+        if !over && retention.max_age.is_some() && count > 0 {
+            jump_stream_start(&mut self.file);
+            let (data, meta) = read_with_meta(&mut self.file)?;
+            over = retention.expired(&data, meta.as_ref(), now, true);
+        }
+
+        if !over {
+            jump(&mut self.file, pos);
+            return Ok(0);
+        }
+
+        jump_stream_start(&mut self.file);
+
+        let mut n = 0;
+        let mut remaining_bytes = bytes;
+
+        while n < count {
+            let start = position(&mut self.file);
+
+            let expired = if retention.max_age.is_some() {
+                let (data, meta) = read_with_meta(&mut self.file)?;
+                retention.expired(&data, meta.as_ref(), now, false)
+            } else {
+                seek_forward(&mut self.file)?;
+                false
+            };
+
+            if !expired && !retention.over_objects(count - n, false) && !retention.over_bytes(remaining_bytes, false) {
+                break;
+            }
+
+            n += 1;
+            remaining_bytes -= position(&mut self.file) - start;
+        }
+
+        jump(&mut self.file, pos);
+        self.trim_front(n)?;
+
+        Ok(n)
+    }
+
+    /// Rewrites the stream in place, keeping only the objects for which map returns Some.
+    /// 
+    /// The stream is written to a temporary file that is renamed over the original,
+    /// so readers never see a partially compacted stream. The cursor is moved to the stream start.
+    pub fn compact(&mut self, map: impl FnMut(Vec<u8>) -> Option<Vec<u8>>) -> Result<(), Error> {
+        let path = self.path.clone();
+        self.compact_into(&path, map)
+    }
+
+    /// Writes the objects for which map returns Some to a fresh stream at dest.
+    /// 
+    /// The stream is written to a temporary file next to dest that is atomically renamed to dest.
+    /// Metadata of the objects is kept. If dest is the path of this stream, the stream is compacted in place.
+    pub fn compact_into(&mut self, dest: &Path, mut map: impl FnMut(Vec<u8>) -> Option<Vec<u8>>) -> Result<(), Error> {
+        self.rewrite_into(dest, |str, out| str.compact_to(out, &mut map))
+    }
+
+    /// Writes a fresh stream at dest through write, which gets this stream and the fresh one.
+    /// 
+    /// The fresh stream is a temporary file next to dest that is atomically renamed to dest once write succeeded.
+    /// If dest is this stream, also through another path, the stream is reopened. The cursor is moved to the stream start.
+    pub(crate) fn rewrite_into(&mut self, dest: &Path, write: impl FnOnce(&mut FileObjStr, &mut FileObjStr) -> Result<(), Error>) -> Result<(), Error> {
+        self.sync();
+
+        // An alias of this stream, like a symlink, is resolved so the rename replaces the open file.
+        let in_place = same_file(&self.path, dest)?;
+        let dest = if in_place { self.path.clone() } else { dest.to_path_buf() };
+
+        let mut name = dest.file_name().unwrap_or_default().to_os_string();
+        name.push(".compact");
+        let tmp = dest.with_file_name(name);
+
+        if tmp.exists() {
+            fs::remove_file(&tmp)?;
+        }
+
+        let res = FileObjStr::new(&tmp)
+            .and_then(|mut out| {
+                write(self, &mut out)?;
+                out.file.sync_all()
+            })
+            .and_then(|_| fs::rename(&tmp, &dest));
+
+        if res.is_err() && tmp.exists() {
+            let _ = fs::remove_file(&tmp);
+        }
+        res?;
+
+        if in_place {
+            self.file = OpenOptions::new()
+                .read(true)
+                .write(true)
+                .open(&self.path)?;
+            self.count = None;
+            self.modified();
+
+            if !self.observers.is_empty() {
+                let len = self.count();
+                self.observers.emit(Event::Rewrite { len });
+            }
+        }
+
+        jump_stream_start(&mut self.file);
+        Ok(())
+    }
+
+    fn compact_to(&mut self, out: &mut FileObjStr, map: &mut impl FnMut(Vec<u8>) -> Option<Vec<u8>>) -> Result<(), Error> {
+        jump_stream_start(&mut self.file);
+
+        loop {
+            let (data, meta) = match read_with_meta(&mut self.file) {
+                Ok(obj) => obj,
+                Err(e) if e.kind() == ErrorKind::UnexpectedEof => break,
+                Err(e) => return Err(e)
+            };
+
+            let Some(data) = map(data) else {
+                continue;
+            };
+
+            match meta {
+                Some(meta) => out.append_with_meta(&data, &meta)?,
+                None => out.append(&data)?
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Moves the objects from index n on to the end of the stream at dest, which is created if missing.
+    /// 
+    /// The frames are copied as raw bytes, using `copy_file_range` where available, before this stream is cut at n,
+    /// so a crash in between duplicates objects but never loses them. The cursor is kept if it lies before n.
+    pub fn split_at(&mut self, n: u64, dest: &Path) -> Result<FileObjStr, Error> {
+        if same_file(&self.path, dest)? {
+            return Err(Error::new(ErrorKind::InvalidInput, "Destination is the source stream."));
+        }
+
+        self.sync();
+        let pos = position(&mut self.file);
+
+        jump_stream_start(&mut self.file);
+        if let Err(e) = seek_forward_n(&mut self.file, n) {
+            jump(&mut self.file, pos);
+            return Err(e);
+        }
+        let start = position(&mut self.file);
+        let end = stream_len(&mut self.file);
+
+        let mut copy = || {
+            let lens = frame_lens(&mut self.file, start, end)?;
+            let mut out = FileObjStr::new(dest)?;
+            out.append_file_range(&mut self.file, start, end, &lens)?;
+            Ok::<_, Error>(out)
+        };
+        let res = copy();
+
+        let out = match res {
+            Ok(out) => out,
+            Err(e) => {
+                jump(&mut self.file, pos);
+                return Err(e);
+            }
+        };
+
+        if start < end {
+            jump(&mut self.file, start);
+            self.cut()?;
+        }
+        jump(&mut self.file, pos.min(start));
+
+        Ok(out)
+    }
+
+    /// Appends the objects of the streams at sources to the stream at dest, which is created if missing.
+    /// 
+    /// The frames are copied as raw bytes, using `copy_file_range` where available, without the terminator of each source.
+    /// All sources are checked for whole frames before anything is copied.
+    pub fn concat<P: AsRef<Path>>(sources: &[P], dest: &Path) -> Result<FileObjStr, Error> {
+        for source in sources {
+            if same_file(source.as_ref(), dest)? {
+                return Err(Error::new(ErrorKind::InvalidInput, "Destination is one of the source streams."));
+            }
+        }
+
+        let mut files = Vec::with_capacity(sources.len());
+        for source in sources {
+            let mut file = File::open(source)?;
+            let len = file.metadata()?.len();
+
+            let mut last = EMPTY;
+            if len == 0 || file.read_at(len - 1, &mut last).is_err() || last != EMPTY {
+                return Err(Error::new(ErrorKind::InvalidData, "Source is not a stream."));
+            }
+
+            let lens = frame_lens(&mut file, 0, len - 1)?;
+            files.push((file, len - 1, lens));
+        }
+
+        let mut out = FileObjStr::new(dest)?;
+        for (mut file, end, lens) in files {
+            out.append_file_range(&mut file, 0, end, &lens)?;
+        }
+
+        jump_stream_start(&mut out.file);
+        Ok(out)
+    }
+
+    /// Appends the frames in the byte range start..end of src, lens are their payload lengths from `frame_lens`.
+    fn append_file_range(&mut self, src: &mut File, start: u64, end: u64, lens: &[u64]) -> Result<(), Error> {
+        self.sync();
+        if lens.is_empty() {
+            return Ok(());
+        }
+
+        src.seek(SeekFrom::Start(start))?;
+        jump_stream_end(&mut self.file);
+        let stream_end = position(&mut self.file);
+
+        let res = io::copy(&mut (&*src).take(end - start), &mut self.file).and_then(|copied| {
+            if copied < end - start {
+                return Err(Error::new(ErrorKind::UnexpectedEof, "Source ended while copying."));
+            }
+            Ok(())
+        });
+
+        if let Err(e) = res {
+            jump(&mut self.file, stream_end);
+            cut(&mut self.file);
+            return Err(e);
+        }
+
+        write_empty_byte(&mut self.file);
+        self.appended(lens)
+    }
 }
 
 impl ObjStr for FileObjStr {
 
     fn seek(&mut self, pos: SeekFrom) -> Result<(), Error> {
+        self.sync();
         
         match pos {
             SeekFrom::Start(pos) => {
@@ -45,10 +558,10 @@ impl ObjStr for FileObjStr {
             },
             SeekFrom::End(pos) => {
                 jump_stream_end(&mut self.file);
-                seek_backward_n(&mut self.file, pos.abs() as u64)?;
+                seek_backward_n(&mut self.file, pos.unsigned_abs())?;
             },
             SeekFrom::Current(pos) => {
-                let pos_abs = pos.abs() as u64;
+                let pos_abs = pos.unsigned_abs();
                 if pos < 0 {
                     seek_backward_n(&mut self.file, pos_abs)?;
                 } else if pos > 0 {
@@ -62,59 +575,226 @@ impl ObjStr for FileObjStr {
     }
 
     fn read(&mut self) -> Result<Vec<u8>, Error> {
-        Ok(read(&mut self.file)?)
+        if let Some(read_ahead) = self.read_ahead.as_mut() {
+            return Ok(read_ahead.read(&mut self.file)?.0);
+        }
+        read(&mut self.file)
     }
 
-    fn len(&mut self, contents: u8, objs: u8) -> Result<u64, Error> {
+    fn read_into(&mut self, buf: &mut Vec<u8>) -> Result<(), Error> {
+        if let Some(read_ahead) = self.read_ahead.as_mut() {
+            return read_ahead.read_into(&mut self.file, buf);
+        }
+        read_into(&mut self.file, buf)
+    }
 
-        let mut inner_lens = Vec::new();
+    fn read_range(&mut self, offset: u64, len: usize) -> Result<Vec<u8>, Error> {
+        self.sync();
+        read_range(&mut self.file, offset, len)
+    }
+
+    fn read_meta(&mut self) -> Result<Option<Meta>, Error> {
+        self.sync();
+        read_meta(&mut self.file)
+    }
 
-        for _ in 0..objs {
-            inner_lens.push(
-                inner_len(&mut self.file)?
-            );
-            seek_forward(&mut self.file)?;
+    fn read_with_meta(&mut self) -> Result<(Vec<u8>, Option<Meta>), Error> {
+        if let Some(read_ahead) = self.read_ahead.as_mut() {
+            return read_ahead.read(&mut self.file);
         }
+        read_with_meta(&mut self.file)
+    }
+
+    fn len(&mut self, contents: u8, objs: u8) -> Result<u64, Error> {
+        self.sync();
+        let pos = position(&mut self.file);
+        stream::inner_len(&mut self.file, pos, contents, objs)
+    }
 
-        seek_backward_n(&mut self.file, objs as u64)?;
+    fn overwrite(&mut self, data: &[&[u8]], objs: u8) -> Result<(), Error> {
+        self.sync();
+        let index = self.observed_index()?;
 
-        let len = len_calc(inner_lens, contents);
+        let pos = position(&mut self.file);
+        let next = stream::overwrite(&mut self.file, pos, data, objs)?;
+        jump(&mut self.file, next);
+        self.modified();
+        self.observed_at(next, index.map(|index| index + data.len() as u64));
 
-        if len < 0 {
-            return Err(Error::new(ErrorKind::Other, "To small to fit that many objects"));
+        if let Some(count) = self.count.as_mut() {
+            *count = *count - objs as u64 + data.len() as u64;
         }
-        
-        Ok(len.abs() as u64)
-    }
 
-    fn overwrite(&mut self, data: Vec<Vec<u8>>, objs: u8) -> Result<(), Error> {
-        if data.len() > u8::MAX as usize {
-            return Err(Error::new(ErrorKind::Other, "To many objects"));
+        if let Some(index) = index {
+            self.observers.emit(Event::Overwrite { range: index..index + data.len() as u64, replaced: objs as u64 });
         }
 
-        let inner_len = self.len(data.len() as u8, objs)?;
-        let actual_len = data.iter().fold(0, |acc, x| acc + x.len()) as u64;
+        Ok(())
+    }
 
-        if inner_len != actual_len {
-            return Err(Error::new(ErrorKind::Other, "Wrong length"));
-        }
+    fn patch(&mut self, offset: u64, data: &[u8]) -> Result<(), Error> {
+        self.sync();
+        let index = self.observed_index()?;
+        patch(&mut self.file, offset, data)?;
+        self.modified();
+        let pos = position(&mut self.file);
+        self.observed_at(pos, index);
 
-        for d in data {
-            write(&mut self.file, d)?;
+        if let Some(index) = index {
+            self.observers.emit(Event::Overwrite { range: index..index + 1, replaced: 1 });
         }
 
         Ok(())
     }
 
-    fn append(&mut self, data: Vec<u8>) -> Result<(), Error> {
+    fn append(&mut self, data: &[u8]) -> Result<(), Error> {
+        self.sync();
         jump_stream_end(&mut self.file);
         write(&mut self.file, data)?;
         write_empty_byte(&mut self.file);
-        Ok(())
+        self.appended(&[data.len() as u64])
+    }
+
+    fn append_with_meta(&mut self, data: &[u8], meta: &Meta) -> Result<(), Error> {
+        self.sync();
+        jump_stream_end(&mut self.file);
+        write_with_meta(&mut self.file, data, meta)?;
+        write_empty_byte(&mut self.file);
+        self.appended(&[data.len() as u64])
     }
 
     fn cut(&mut self) -> Result<(), Error> {
+        self.sync();
+        let index = self.observed_index()?;
+        let pos = position(&mut self.file);
         cut(&mut self.file);
+        self.modified();
+        self.observed_at(pos, index);
+        self.count = index;
+
+        if let Some(len) = index {
+            self.observers.emit(Event::Cut { len });
+        }
+
+        Ok(())
+    }
+
+    fn trim_front(&mut self, n: u64) -> Result<(), Error> {
+        self.sync();
+        let pos = position(&mut self.file);
+
+        jump_stream_start(&mut self.file);
+        if let Err(e) = seek_forward_n(&mut self.file, n) {
+            jump(&mut self.file, pos);
+            return Err(e);
+        }
+
+        let offset = position(&mut self.file);
+        shift_front(&mut self.file, offset);
+        jump(&mut self.file, pos.saturating_sub(offset));
+
+        let index = self.index
+            .filter(|&(frame, index, generation)| generation == self.generation() && frame >= offset && index >= n)
+            .map(|(frame, index, _)| (frame - offset, index - n));
+        self.modified();
+        self.index = index.map(|(frame, index)| (frame, index, self.generation()));
+
+        if let Some(count) = self.count.as_mut() {
+            *count -= n;
+        }
+
+        if !self.observers.is_empty() {
+            self.observers.emit(Event::Trim { objs: n });
+        }
+
         Ok(())
     }
-}
\ No newline at end of file
+
+    /// Bisects the byte range of the stream instead of the object indices, so pred is evaluated O(log n) times
+    /// and only the frames found by `sync_to_frame` are read.
+    /// 
+    /// The returned index is counted by walking frame heads from the nearest frame with a known index.
+    /// If a payload embeds valid frames, a probe can land on one of them and read it as an object.
+    fn partition_point<P>(&mut self, mut pred: P) -> Result<u64, Error>
+    where
+        P: FnMut(&[u8]) -> bool
+    {
+        self.sync();
+        let end = stream_len(&mut self.file);
+
+        // All frames before lo satisfy pred, the frame at hi does not or hi is the stream end.
+        // No frame starts in top..hi, so the first frame failing pred is at hi or starts in lo..top.
+        let mut lo = 0;
+        let mut hi = end;
+        let mut top = end;
+
+        while lo < top {
+            let mid = lo + (top - lo) / 2;
+            let frame = match sync_frame(&mut self.file, mid, end)? {
+                Some(frame) if frame < top => frame,
+                _ => {
+                    top = mid;
+                    continue;
+                }
+            };
+
+            jump(&mut self.file, frame);
+            if pred(&read(&mut self.file)?) {
+                lo = position(&mut self.file);
+            } else {
+                hi = frame;
+                top = mid;
+            }
+        }
+
+        let index = self.index_at(hi)?;
+        jump(&mut self.file, hi);
+        self.observed_at(hi, Some(index));
+        Ok(index)
+    }
+}
+
+/// Payload lengths of the frames in the byte range start..end of storage, read from the frame headers.
+/// 
+/// If a frame exceeds the range or its mirrored lengths disagree, error occurs.
+fn frame_lens<S: Storage + ?Sized>(storage: &mut S, start: u64, end: u64) -> Result<Vec<u64>, Error> {
+    let mut lens = Vec::new();
+    let mut pos = start;
+
+    while pos < end {
+        if end - pos < 2 * OP_LEN as u64 {
+            return Err(Error::new(ErrorKind::InvalidData, "Frame exceeds the copied range."));
+        }
+
+        let (len, has_meta) = stream::head(storage, pos)?;
+        let frame_end = pos + stream::frame_len(len);
+        if frame_end > end {
+            return Err(Error::new(ErrorKind::InvalidData, "Frame exceeds the copied range."));
+        }
+
+        let mut tail = [0; OP_LEN as usize];
+        storage.read_at(frame_end - OP_LEN as u64, &mut tail)?;
+        if u32::from_be_bytes(tail) != stream::raw_len(len, has_meta) {
+            return Err(Error::new(ErrorKind::InvalidData, "Frame lengths do not match."));
+        }
+
+        let meta = if has_meta {
+            stream::meta_range_at(storage, pos, len)?.end as u32
+        } else {
+            0
+        };
+
+        lens.push((len - meta) as u64);
+        pos = frame_end;
+    }
+
+    Ok(lens)
+}
+
+/// Returns whether both paths point to the same existing file.
+fn same_file(a: &Path, b: &Path) -> Result<bool, Error> {
+    if !a.exists() || !b.exists() {
+        return Ok(false);
+    }
+    Ok(fs::canonicalize(a)? == fs::canonicalize(b)?)
+}
@@ -0,0 +1,109 @@
+use std::{fs::File, io::Error, path::Path, sync::{atomic::{AtomicU64, Ordering}, Arc}, thread, time::{Duration, Instant}};
+
+use crate::frame::{stream, Storage as _};
+
+use super::helpers::{frame_end, strip_meta, OP_LEN};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Iterator over the objects of a stream that waits for new objects at the stream end, like `tail -f`.
+///
+/// A frame is only yielded once its mirrored lengths agree and at least one byte follows it,
+/// which an appending stream writes last, so objects still being appended by another process are never returned partially.
+/// The stream is polled, by default every 100ms and without a timeout.
+/// 
+/// Cuts and modifications through the stream the follower was created from are detected by its generation,
+/// other processes are only detected when they cut the stream below the follower.
+pub struct Follow {
+    file: File,
+    pos: u64,
+    generation: Arc<AtomicU64>,
+    pinned: u64,
+    poll_interval: Duration,
+    timeout: Option<Duration>
+}
+
+impl Follow {
+    pub(crate) fn new(path: &Path, pos: u64, generation: Arc<AtomicU64>) -> Result<Follow, Error> {
+        let pinned = generation.load(Ordering::SeqCst);
+        Ok(
+            Follow {
+                file: File::open(path)?,
+                pos,
+                generation,
+                pinned,
+                poll_interval: POLL_INTERVAL,
+                timeout: None
+            }
+        )
+    }
+
+    /// Sets how often the stream is checked for new objects.
+    pub fn poll_interval(mut self, interval: Duration) -> Follow {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// Ends the iteration if no new object arrives within timeout.
+    pub fn timeout(mut self, timeout: Duration) -> Follow {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Returns the next object if it is completely written, without waiting.
+    ///
+    /// If the stream was cut or modified, error occurs.
+    pub fn try_next(&mut self) -> Result<Option<Vec<u8>>, Error> {
+        if self.generation.load(Ordering::SeqCst) != self.pinned {
+            return Err(Error::other("Stream was modified behind the follower."));
+        }
+
+        let len = self.file.metadata()?.len();
+
+        if len <= self.pos {
+            return Err(Error::other("Stream was cut behind the follower."));
+        }
+
+        let Some(next) = frame_end(&mut self.file, self.pos, len - 1)? else {
+            return Ok(None);
+        };
+
+        let (_, has_meta) = stream::head(&mut self.file, self.pos)?;
+
+        let mut data = vec![0; (next - self.pos) as usize - 2 * OP_LEN as usize];
+        self.file.read_at(self.pos + OP_LEN as u64, &mut data)?;
+        strip_meta(&mut data, has_meta)?;
+
+        self.pos = next;
+        Ok(Some(data))
+    }
+}
+
+impl Iterator for Follow {
+    type Item = Result<Vec<u8>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let deadline = self.timeout.map(|timeout| Instant::now() + timeout);
+
+        loop {
+            match self.try_next() {
+                Ok(Some(data)) => return Some(Ok(data)),
+                Ok(None) => {},
+                Err(e) => return Some(Err(e))
+            }
+
+            let wait = match deadline {
+                Some(deadline) => {
+                    let left = deadline.saturating_duration_since(Instant::now());
+                    if left.is_zero() {
+                        return None;
+                    }
+                    left.min(self.poll_interval)
+                },
+                None => self.poll_interval
+            };
+
+            thread::sleep(wait);
+        }
+    }
+}
@@ -1,8 +1,12 @@
 use core::panic;
 use std::{fs::File, io::{Error, ErrorKind, Read as _, Seek as _, SeekFrom, Write as _}};
 
-pub const EMPTY: [u8; 1] = [0; 1];
-pub const OP_LEN: u8 = 4;
+use crate::{api::Meta, frame::{encode_meta_inner, stream, Storage as _}};
+
+pub use crate::frame::{decode_head, encode_frame, EMPTY, HEAD_LEN, OP_LEN};
+pub use crate::frame::stream::{split_meta, strip_meta};
+
+pub const BLOCK_LEN: usize = 64 * 1024;
 
 pub fn write_empty_byte(file: &mut File) {
     let res = file.write(&EMPTY);
@@ -16,7 +20,7 @@ pub fn write_empty_byte(file: &mut File) {
 
 pub fn cut(file: &mut File) {
     
-    let pos = file.seek(SeekFrom::Current(0));
+    let pos = file.stream_position();
 
     if let Err(e) = pos {
         panic!("Failed to cut file: {}", e);
@@ -60,241 +64,335 @@ pub fn jump_stream_end(file: &mut File) {
     }
 }
 
-pub fn catch_stream_read(file: &mut File, read: usize, predict: usize, fix_fn: fn(file: &mut File)) -> Result<(), Error> {
-    if read != predict {
-        fix_fn(file);
-        return Err(
-            Error::new(
-                ErrorKind::UnexpectedEof,
-                "Readed data length mismatch."
-            )
-        );
-    }
-    Ok(())
+pub fn write(file: &mut File, data: &[u8]) -> Result<(), Error> {
+    write_frame(file, data, false)
 }
 
-pub fn write(file: &mut File, data: Vec<u8>) -> Result<(), Error> {
-
-    if data.len() > u32::MAX as usize {
-        return Err(
-            Error::new(
-                ErrorKind::InvalidInput,
-                "Data length is too long."
-            )
-        );
-    }
+pub fn write_with_meta(file: &mut File, data: &[u8], meta: &Meta) -> Result<(), Error> {
+    let inner = encode_meta_inner(&meta.encode()?, data);
+    write_frame(file, &inner, true)
+}
 
+fn write_frame(file: &mut File, inner: &[u8], has_meta: bool) -> Result<(), Error> {
     let mut obj = Vec::new();
-    let op = u32::to_be_bytes(data.len() as u32);
-
-    obj.extend_from_slice(&op);
-    obj.extend_from_slice(&data);
-    obj.extend_from_slice(&op);
+    encode_frame(&mut obj, inner, has_meta)?;
+    write_raw(file, &obj);
+    Ok(())
+}
 
-    let res = file.write(&obj);
+pub fn write_raw(file: &mut File, buf: &[u8]) {
+    let res = file.write_all(buf);
 
     if let Err(e) = res {
         panic!("Failed to write data: {}", e);
     }
-
-    Ok(())
 }
 
 pub fn read(file: &mut File) -> Result<Vec<u8>, Error> {
+    Ok(read_with_meta(file)?.0)
+}
 
-    let mut len_buf_left: [u8; OP_LEN as usize] = [0; OP_LEN as usize];
-    let res = file.read(&mut len_buf_left);
+pub fn read_with_meta(file: &mut File) -> Result<(Vec<u8>, Option<Meta>), Error> {
+    let mut inner = Vec::new();
+    let has_meta = read_inner_into(file, &mut inner)?;
+    split_meta(inner, has_meta)
+}
 
-    if let Err(e) = res {
-        panic!("Failed to read data: {}", e);
+pub fn read_into(file: &mut File, buf: &mut Vec<u8>) -> Result<(), Error> {
+    let has_meta = read_inner_into(file, buf)?;
+    strip_meta(buf, has_meta)
+}
+
+pub fn read_meta(file: &mut File) -> Result<Option<Meta>, Error> {
+    let pos = position(file);
+    let (meta, next) = stream::read_meta(file, pos).inspect_err(|e| at_stream_end(file, e))?;
+    jump(file, next);
+    Ok(meta)
+}
+
+fn read_inner_into(file: &mut File, buf: &mut Vec<u8>) -> Result<bool, Error> {
+    let pos = position(file);
+    let (has_meta, next) = stream::read_inner_into(file, pos, buf).inspect_err(|e| at_stream_end(file, e))?;
+    jump(file, next);
+    Ok(has_meta)
+}
+
+/// Moves the cursor to the stream terminator if e reports the stream end.
+fn at_stream_end(file: &mut File, e: &Error) {
+    if e.kind() == ErrorKind::UnexpectedEof {
+        jump_stream_end(file);
     }
+}
 
-    let res = res.unwrap();
+pub fn seek_forward(file: &mut File) -> Result<(), Error> {
+    seek_forward_n(file, 1)
+}
 
-    catch_stream_read(
-        file,
-        res,
-        OP_LEN as usize,
-        jump_stream_end
-    )?;
+pub fn seek_forward_n(file: &mut File, n: u64) -> Result<(), Error> {
+    let mut pos = position(file);
+    let res = stream::skip_forward(file, &mut pos, n);
+    jump(file, pos);
+    res
+}
 
-    let len_left = u32::from_be_bytes(len_buf_left) as usize;
+pub fn seek_backward_n(file: &mut File, n: u64) -> Result<(), Error> {
+    let mut pos = position(file);
+    let res = stream::skip_backward(file, &mut pos, n);
+    jump(file, pos);
+    res
+}
 
-    let mut data = vec![0; len_left];
-    let res = file.read(&mut data);
+pub fn position(file: &mut File) -> u64 {
+    let res = file.stream_position();
 
     if let Err(e) = res {
-        panic!("Failed to read data: {}", e);
+        panic!("Failed to get stream position: {}", e);
     }
 
-    let res = res.unwrap();
+    res.unwrap()
+}
 
-    let catch = catch_stream_read(
-        file,
-        res,
-        len_left,
-        jump_stream_start
-    );
+pub fn jump(file: &mut File, pos: u64) {
+    let res = file.seek(SeekFrom::Start(pos));
 
-    if let Err(e) = catch {
-        panic!("Failed to read data: {}", e);
+    if let Err(e) = res {
+        panic!("Failed to jump: {}", e);
     }
+}
 
-    let mut len_buf_right: [u8; OP_LEN as usize] = [0; OP_LEN as usize];
-    let res = file.read(&mut len_buf_right);
+pub fn stream_len(file: &mut File) -> u64 {
+    let res = file.metadata();
 
     if let Err(e) = res {
-        panic!("Failed to read data: {}", e);
+        panic!("Failed to get stream length: {}", e);
     }
 
-    let res = res.unwrap();
-
-    let catch = catch_stream_read(
-        file,
-        res,
-        OP_LEN as usize,
-        jump_stream_start
-    );
+    res.unwrap().len() - EMPTY.len() as u64
+}
 
-    if let Err(e) = catch {
-        panic!("Failed to read data: {}", e);
-    }
+pub fn count(file: &mut File) -> u64 {
+    let end = stream_len(file);
 
-    let len_right = u32::from_be_bytes(len_buf_right) as usize;
-    
-    if len_left != len_right {
-        panic!("Data length mismatch.");
+    let mut pos = 0;
+    let mut count = 0;
+    while let Ok(next) = stream::next(file, pos, end) {
+        pos = next;
+        count += 1;
     }
 
-    Ok(data)
+    count
 }
 
-pub fn seek_forward(file: &mut File) -> Result<(), Error> {
-    let mut len_buf: [u8; OP_LEN as usize] = [0; OP_LEN as usize];
+pub fn shift_front(file: &mut File, offset: u64) {
+    let mut buf = vec![0; BLOCK_LEN];
+    let mut read_pos = offset;
+    let mut write_pos = 0;
 
-    let res = file.read(&mut len_buf);
+    loop {
+        jump(file, read_pos);
 
-    if let Err(e) = res {
-        panic!("Failed to read data: {}", e);
-    }
+        let res = file.read(&mut buf);
+
+        if let Err(e) = res {
+            panic!("Failed to shift data: {}", e);
+        }
+
+        let res = res.unwrap();
 
-    catch_stream_read(
-        file,
-        res?,
-        OP_LEN as usize,
-        jump_stream_end
-    )?;
+        if res == 0 {
+            break;
+        }
 
-    let len = u32::from_be_bytes(len_buf) as i64;
+        jump(file, write_pos);
 
-    let catch = file.seek(SeekFrom::Current(len));
+        if let Err(e) = file.write_all(&buf[..res]) {
+            panic!("Failed to shift data: {}", e);
+        }
 
-    if let Err(e) = catch {
-        panic!("Failed to seek forward: {}", e);
+        read_pos += res as u64;
+        write_pos += res as u64;
     }
 
-    let catch = file.seek(SeekFrom::Current(OP_LEN as i64));
+    let res = file.set_len(write_pos);
 
-    if let Err(e) = catch {
-        panic!("Failed to seek forward: {}", e);
+    if let Err(e) = res {
+        panic!("Failed to shift data: {}", e);
     }
+}
 
-    Ok(())
+pub fn read_range(file: &mut File, offset: u64, len: usize) -> Result<Vec<u8>, Error> {
+    let pos = position(file);
+    let start = stream::payload_range(file, pos, offset, len as u64)?;
+
+    let mut data = vec![0; len];
+    file.read_at(start, &mut data)?;
+
+    Ok(data)
 }
 
-pub fn seek_backward(file: &mut File) -> Result<(), Error> {
-    let mut len_buf: [u8; OP_LEN as usize] = [0; OP_LEN as usize];
+pub fn patch(file: &mut File, offset: u64, data: &[u8]) -> Result<(), Error> {
+    let pos = position(file);
+    let start = stream::payload_range(file, pos, offset, data.len() as u64)?;
+    file.write_at(start, data)
+}
 
-    let res = file.seek(SeekFrom::Current(-(OP_LEN as i64)));
+pub fn index_of(file: &mut File, pos: u64) -> Result<u64, Error> {
+    walk_index(file, 0, 0, pos)
+}
 
-    if let Err(e) = res {
-        if e.kind() == ErrorKind::InvalidInput {
-            return Err(
-                Error::new(
-                    ErrorKind::UnexpectedEof,
-                    "Reached stream start."
-                )
-            )
-        }
-        panic!("Failed to seek backward: {}", e);
+/// Returns the index of the frame at pos by walking from the frame at frame, whose index is index.
+pub fn walk_index(file: &mut File, mut frame: u64, mut index: u64, pos: u64) -> Result<u64, Error> {
+    let end = stream_len(file);
+
+    while frame < pos {
+        frame = stream::next(file, frame, end)?;
+        index += 1;
+    }
+    while frame > pos {
+        frame = stream::prev(file, frame)?;
+        index -= 1;
     }
 
-    let res = file.read(&mut len_buf);
-    let catch = catch_stream_read(
-        file,
-        res?,
-        OP_LEN as usize,
-        jump_stream_start
-    );
+    Ok(index)
+}
+
+/// Returns the start of the first frame at or after pos.
+/// 
+/// Candidates are validated by their mirrored lengths, the lengths of the neighbouring frames and
+/// the stream end, so that random payload bytes are unlikely to be taken for a frame.
+pub fn sync_frame(file: &mut File, pos: u64, end: u64) -> Result<Option<u64>, Error> {
+    let mut window = vec![0; BLOCK_LEN + HEAD_LEN as usize - 1];
+    let mut base = pos;
+
+    while base < end {
+        let n = read_at(file, &mut window, base)?;
+
+        for i in 0..n.saturating_sub(OP_LEN as usize - 1).min(BLOCK_LEN) {
+            let candidate = base + i as u64;
+            if candidate >= end {
+                return Ok(None);
+            }
+
+            let Some((len, _)) = decode_head(&window[i..n]) else {
+                continue;
+            };
+            if candidate + stream::frame_len(len) > end {
+                continue;
+            }
+
+            if is_frame(file, candidate, end)? {
+                return Ok(Some(candidate));
+            }
+        }
 
-    if let Err(e) = catch {
-        panic!("Failed to seek backward: {}", e);
+        base += BLOCK_LEN as u64;
     }
 
-    let len = u32::from_be_bytes(len_buf) as i64;
+    Ok(None)
+}
 
-    let res =  file.seek(SeekFrom::Current(-len -2*OP_LEN as i64));
+/// Checks whether a frame starts at pos, whose neighbours are frames or the stream bounds.
+/// 
+/// The following frame has to be valid. The preceding frame has to be valid as well,
+/// unless it is damaged and the frame after the following one is valid instead.
+pub fn is_frame(file: &mut File, pos: u64, end: u64) -> Result<bool, Error> {
+    let Some(next) = frame_end(file, pos, end)? else {
+        return Ok(false);
+    };
+
+    if next == end {
+        return Ok(true);
+    }
 
-    if let Err(e) = res {
-        if e.kind() == ErrorKind::InvalidInput {
-            return Err(
-                Error::new(
-                    ErrorKind::UnexpectedEof,
-                    "Reached stream start."
-                )
-            )
-        }
-        panic!("Failed to seek backward: {}", e);
+    let Some(after_next) = frame_end(file, next, end)? else {
+        return Ok(false);
+    };
+
+    if pos == 0 || after_next == end || is_frame_end(file, pos)? {
+        return Ok(true);
     }
 
-    Ok(())
+    Ok(frame_end(file, after_next, end)?.is_some())
 }
 
-pub fn seek_forward_n(file: &mut File, n: u64) -> Result<(), Error> {
-    for _ in 0..n {
-        seek_forward(file)?;
+/// Checks whether a frame ends at pos.
+fn is_frame_end(file: &mut File, pos: u64) -> Result<bool, Error> {
+    if pos < 2 * OP_LEN as u64 {
+        return Ok(false);
     }
-    Ok(())
+
+    let prev = match stream::prev(file, pos) {
+        Ok(prev) => prev,
+        Err(e) if e.kind() == ErrorKind::InvalidData => return Ok(false),
+        Err(e) => return Err(e)
+    };
+
+    let mut len_buf_left: [u8; OP_LEN as usize] = [0; OP_LEN as usize];
+    file.read_at(prev, &mut len_buf_left)?;
+
+    let mut len_buf_right: [u8; OP_LEN as usize] = [0; OP_LEN as usize];
+    file.read_at(pos - OP_LEN as u64, &mut len_buf_right)?;
+
+    Ok(len_buf_left == len_buf_right)
 }
 
-pub fn seek_backward_n(file: &mut File, n: u64) -> Result<(), Error> {
-    for _ in 0..n {
-        seek_backward(file)?;
+/// Returns the end of the frame at pos, if its mirrored lengths agree, its metadata section fits and it ends before end.
+pub fn frame_end(file: &mut File, pos: u64, end: u64) -> Result<Option<u64>, Error> {
+    if pos + 2 * OP_LEN as u64 > end {
+        return Ok(None);
     }
-    Ok(())
-}
 
-pub fn inner_len(file: &mut File) -> Result<u32, Error> {
-    let mut len_buf: [u8; OP_LEN as usize] = [0; OP_LEN as usize];
-    let res = file.read(&mut len_buf);
+    let (len, has_meta) = stream::head(file, pos)?;
+    let next = pos + stream::frame_len(len);
 
-    if let Err(e) = res {
-        panic!("Failed to read data: {}", e);
+    if next > end {
+        return Ok(None);
     }
 
-    catch_stream_read(
-        file,
-        res?,
-        OP_LEN as usize,
-        jump_stream_end
-    )?;
-
-    let len = u32::from_be_bytes(len_buf) as u32;
+    if has_meta && stream::meta_range_at(file, pos, len).is_err() {
+        return Ok(None);
+    }
 
-    let res = file.seek(SeekFrom::Current(-(OP_LEN as i64)));
+    let mut len_buf_right: [u8; OP_LEN as usize] = [0; OP_LEN as usize];
+    file.read_at(next - OP_LEN as u64, &mut len_buf_right)?;
 
-    if let Err(e) = res {
-        panic!("Failed to seek backward: {}", e);
+    if u32::from_be_bytes(len_buf_right) != stream::raw_len(len, has_meta) {
+        return Ok(None);
     }
 
-    Ok(len)
+    Ok(Some(next))
 }
 
-pub fn len_calc(inner_lens: Vec<u32>, op_sets: u8) -> i64 {
-    let mut len = 0;
-    for inner_len in inner_lens {
-        len += inner_len as u64 + 2*OP_LEN as u64;
+#[cfg(unix)]
+fn read_at(file: &mut File, buf: &mut [u8], pos: u64) -> Result<usize, Error> {
+    let mut n = 0;
+    while n < buf.len() {
+        match std::os::unix::fs::FileExt::read_at(file, &mut buf[n..], pos + n as u64) {
+            Ok(0) => break,
+            Ok(res) => n += res,
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e)
+        }
     }
-    let len = len as i64 - OP_LEN as i64 * 2 * op_sets as i64;
-    len
+    Ok(n)
+}
+
+#[cfg(not(unix))]
+fn read_at(file: &mut File, buf: &mut [u8], pos: u64) -> Result<usize, Error> {
+    let current = position(file);
+    jump(file, pos);
+    let mut n = 0;
+    let res = loop {
+        match file.read(&mut buf[n..]) {
+            Ok(0) => break Ok(n),
+            Ok(res) => n += res,
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => break Err(e)
+        }
+        if n == buf.len() {
+            break Ok(n);
+        }
+    };
+    jump(file, current);
+    res
 }
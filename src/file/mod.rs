@@ -1,6 +1,34 @@
 
+mod buffered_appender;
+
 mod file_obj_str;
 
+mod follow;
+
 mod helpers;
 
+mod observer;
+
+#[cfg(feature = "rayon")]
+mod par_iter;
+
+mod read_ahead;
+
+mod replication;
+
+mod retention;
+
+mod salvage;
+
+mod snapshot;
+
+pub use buffered_appender::BufferedAppender;
 pub use file_obj_str::FileObjStr;
+pub use follow::Follow;
+pub use observer::Event;
+#[cfg(feature = "rayon")]
+pub use par_iter::{ParIter, RangeIter};
+pub use replication::{apply, Replicator};
+pub use retention::{Retention, Timestamp};
+pub use salvage::{salvage, Salvage};
+pub use snapshot::Snapshot;
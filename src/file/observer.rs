@@ -0,0 +1,51 @@
+use std::{ops::Range, sync::mpsc::Sender};
+
+/// Change of a stream reported to its observers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    /// An object was appended at index, len is the length of its payload.
+    Append { index: u64, len: u64 },
+    /// The objects in range were written over the replaced objects starting at range.start.
+    Overwrite { range: Range<u64>, replaced: u64 },
+    /// The stream was cut to len objects.
+    Cut { len: u64 },
+    /// The first objs objects were deleted, the following objects moved to the front.
+    Trim { objs: u64 },
+    /// The whole stream was rewritten and now holds len objects.
+    Rewrite { len: u64 }
+}
+
+enum Observer {
+    Callback(Box<dyn FnMut(&Event) + Send>),
+    Channel(Sender<Event>)
+}
+
+#[derive(Default)]
+pub(crate) struct Observers {
+    list: Vec<Observer>
+}
+
+impl Observers {
+    pub fn subscribe(&mut self, f: impl FnMut(&Event) + Send + 'static) {
+        self.list.push(Observer::Callback(Box::new(f)));
+    }
+
+    pub fn channel(&mut self, sender: Sender<Event>) {
+        self.list.push(Observer::Channel(sender));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    /// Delivers event to every observer, channels whose receiver is gone are dropped.
+    pub fn emit(&mut self, event: Event) {
+        self.list.retain_mut(|observer| match observer {
+            Observer::Callback(f) => {
+                f(&event);
+                true
+            },
+            Observer::Channel(sender) => sender.send(event.clone()).is_ok()
+        });
+    }
+}
@@ -0,0 +1,92 @@
+use std::{fs::File, io::{Error, ErrorKind}, path::PathBuf};
+
+use rayon::iter::{IndexedParallelIterator, IntoParallelIterator as _, ParallelIterator};
+
+use super::{helpers::BLOCK_LEN, read_ahead::ReadAhead};
+
+/// Parallel scan over the objects of a stream, split into byte ranges on frame boundaries.
+/// 
+/// Every range is read sequentially through its own file handle.
+pub struct ParIter {
+    path: PathBuf,
+    ranges: Vec<(u64, u64)>
+}
+
+impl ParIter {
+    pub(crate) fn new(path: PathBuf, ranges: Vec<(u64, u64)>) -> ParIter {
+        ParIter {
+            path,
+            ranges
+        }
+    }
+
+    /// Yields all objects in parallel.
+    /// 
+    /// Objects are processed in no particular order, collecting them keeps the stream order.
+    pub fn objects(self) -> impl ParallelIterator<Item = Result<Vec<u8>, Error>> {
+        self.ranges().flat_map_iter(|range| range)
+    }
+
+    /// Yields one sequential reader per byte range, in stream order.
+    pub fn ranges(self) -> impl IndexedParallelIterator<Item = RangeIter> {
+        let path = self.path;
+        self.ranges
+            .into_par_iter()
+            .map(move |(start, end)| RangeIter::new(File::open(&path), start, end))
+    }
+}
+
+/// Sequential reader over the objects of one byte range.
+/// 
+/// The last object of the range has to end exactly where the next range starts, otherwise the boundary
+/// was taken from a frame-like sequence inside a payload and the reader fails with an error.
+pub struct RangeIter {
+    file: Option<Result<File, Error>>,
+    read_ahead: ReadAhead,
+    start: u64,
+    end: u64
+}
+
+impl RangeIter {
+    fn new(file: Result<File, Error>, start: u64, end: u64) -> RangeIter {
+        RangeIter {
+            file: Some(file),
+            read_ahead: ReadAhead::new(BLOCK_LEN),
+            start,
+            end
+        }
+    }
+}
+
+impl Iterator for RangeIter {
+    type Item = Result<Vec<u8>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let file = match self.file.as_mut()? {
+            Ok(file) => file,
+            Err(_) => return self.file.take().and_then(Result::err).map(Err)
+        };
+
+        let pos = self.read_ahead.position(file, self.start);
+        if pos >= self.end {
+            self.file = None;
+            return None;
+        }
+
+        match self.read_ahead.read(file) {
+            Ok(_) if self.read_ahead.position(file, self.start) > self.end => {
+                self.file = None;
+                Some(Err(Error::new(ErrorKind::InvalidData, "Range does not end on a frame boundary.")))
+            },
+            Ok((data, _)) => Some(Ok(data)),
+            Err(e) if e.kind() == ErrorKind::UnexpectedEof => {
+                self.file = None;
+                None
+            },
+            Err(e) => {
+                self.file = None;
+                Some(Err(e))
+            }
+        }
+    }
+}
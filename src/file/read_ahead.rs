@@ -0,0 +1,150 @@
+use std::{fs::File, io::{Error, ErrorKind, Read as _}, ops::Range};
+
+use crate::api::Meta;
+
+use super::helpers::{decode_head, jump, jump_stream_end, position, split_meta, strip_meta, HEAD_LEN, OP_LEN};
+
+/// Block buffer that parses frames out of large reads instead of reading every frame field separately.
+///
+/// While the buffer is in use, the file cursor is ahead of the stream cursor.
+/// The buffer has to be synced before the file is accessed any other way.
+pub(crate) struct ReadAhead {
+    buf: Vec<u8>,
+    start: u64,
+    pos: Option<u64>,
+    capacity: usize
+}
+
+impl ReadAhead {
+    pub(crate) fn new(capacity: usize) -> ReadAhead {
+        ReadAhead {
+            buf: Vec::new(),
+            start: 0,
+            pos: None,
+            capacity
+        }
+    }
+
+    pub(crate) fn read(&mut self, file: &mut File) -> Result<(Vec<u8>, Option<Meta>), Error> {
+        let (inner, has_meta) = self.next_frame(file)?;
+        split_meta(self.buf[inner].to_vec(), has_meta)
+    }
+
+    pub(crate) fn read_into(&mut self, file: &mut File, buf: &mut Vec<u8>) -> Result<(), Error> {
+        let (inner, has_meta) = self.next_frame(file)?;
+        buf.clear();
+        buf.extend_from_slice(&self.buf[inner]);
+        strip_meta(buf, has_meta)
+    }
+
+    /// Returns the range of the inner frame bytes in the buffer and advances the stream cursor.
+    fn next_frame(&mut self, file: &mut File) -> Result<(Range<usize>, bool), Error> {
+        let pos = match self.pos {
+            Some(pos) => pos,
+            None => position(file)
+        };
+
+        if self.available(pos) < HEAD_LEN as usize {
+            self.fill(file, pos, HEAD_LEN as usize);
+        }
+
+        if self.available(pos) < HEAD_LEN as usize {
+            return Err(self.eof(file));
+        }
+
+        let offset = (pos - self.start) as usize;
+        let (len, has_meta) = decode_head(&self.buf[offset..]).unwrap();
+        let frame_len = len as usize + 2 * OP_LEN as usize;
+
+        if self.available(pos) < frame_len {
+            // The length comes from the file, so it is checked against the file before the buffer grows to it.
+            if pos + frame_len as u64 > file.metadata()?.len() {
+                return Err(self.exceeds_end(file, pos));
+            }
+            self.fill(file, pos, frame_len);
+        }
+
+        if self.available(pos) < frame_len {
+            return Err(self.exceeds_end(file, pos));
+        }
+
+        let offset = (pos - self.start) as usize;
+        let frame = &self.buf[offset..offset + frame_len];
+
+        if frame[..OP_LEN as usize] != frame[frame_len - OP_LEN as usize..] {
+            return Err(Error::new(ErrorKind::InvalidData, "Data length mismatch."));
+        }
+
+        self.pos = Some(pos + frame_len as u64);
+
+        Ok((offset + OP_LEN as usize..offset + frame_len - OP_LEN as usize, has_meta))
+    }
+
+    /// Returns the stream cursor, if nothing was read yet, the file cursor is moved to start.
+    #[cfg(feature = "rayon")]
+    pub(crate) fn position(&mut self, file: &mut File, start: u64) -> u64 {
+        match self.pos {
+            Some(pos) => pos,
+            None => {
+                jump(file, start);
+                start
+            }
+        }
+    }
+
+    /// Moves the file cursor back to the stream cursor and drops the buffer.
+    pub(crate) fn sync(&mut self, file: &mut File) {
+        if let Some(pos) = self.pos.take() {
+            jump(file, pos);
+        }
+        self.buf.clear();
+    }
+
+    fn available(&self, pos: u64) -> usize {
+        if self.pos.is_none() || pos < self.start || pos > self.start + self.buf.len() as u64 {
+            return 0;
+        }
+        self.buf.len() - (pos - self.start) as usize
+    }
+
+    fn fill(&mut self, file: &mut File, pos: u64, min: usize) {
+        self.buf.clear();
+        self.buf.resize(self.capacity.max(min), 0);
+
+        jump(file, pos);
+
+        let mut n = 0;
+        while n < self.buf.len() {
+            match file.read(&mut self.buf[n..]) {
+                Ok(0) => break,
+                Ok(res) => n += res,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => panic!("Failed to read data: {}", e)
+            }
+        }
+
+        self.buf.truncate(n);
+        self.start = pos;
+        self.pos = Some(pos);
+    }
+
+    /// Drops the buffer, leaves the cursor at the frame at pos and reports that the frame is cut off.
+    fn exceeds_end(&mut self, file: &mut File, pos: u64) -> Error {
+        self.pos = None;
+        self.buf.clear();
+        jump(file, pos);
+
+        Error::new(ErrorKind::InvalidData, "Frame exceeds stream end.")
+    }
+
+    fn eof(&mut self, file: &mut File) -> Error {
+        self.pos = None;
+        self.buf.clear();
+        jump_stream_end(file);
+
+        Error::new(
+            ErrorKind::UnexpectedEof,
+            "Readed data length mismatch."
+        )
+    }
+}
@@ -0,0 +1,278 @@
+use std::{fs::File, io::{Error, ErrorKind, Read, SeekFrom, Write}, sync::mpsc::Receiver};
+
+use crate::{api::{Meta, ObjStr as _}, frame::{meta_range, stream, Storage as _, LEN_MASK}, util::crc32c::crc32c};
+
+use super::{helpers::{decode_head, jump_stream_start, position, seek_forward, seek_forward_n, stream_len, OP_LEN}, Event, FileObjStr};
+
+const FRAMES: u8 = 1;
+const TRIM: u8 = 2;
+
+/// Frames are shipped in messages of about this many bytes.
+const BATCH_LEN: u64 = 1024 * 1024;
+
+/// Longest body of a message, a batch can exceed BATCH_LEN by one frame.
+const MAX_BODY_LEN: u64 = BATCH_LEN + 2 * OP_LEN as u64 + LEN_MASK as u64;
+
+struct Message {
+    kind: u8,
+    index: u64,
+    prev: u32,
+    body: Vec<u8>
+}
+
+/// Ships the changes of a primary stream to a follower copy.
+///
+/// Messages are `[u8 kind][u64 index][u32 crc of previous frame][u32 crc of body][u32 body len][body]`.
+/// A frames message replaces the follower objects from index on with the raw frames in its body,
+/// so appends, overwrites and cuts are all shipped the same way. A trim message deletes index objects from the front.
+pub struct Replicator {
+    events: Receiver<Event>,
+    pending: Vec<Event>,
+    follower_len: u64,
+    dirty: Option<u64>
+}
+
+impl Replicator {
+    /// Starts replicating primary to a follower that already holds its first follower_len objects.
+    ///
+    /// After a restart the follower count is passed to resume where replication stopped.
+    /// If the follower diverged from the primary meanwhile, applying the next message fails.
+    pub fn new(primary: &mut FileObjStr, follower_len: u64) -> Replicator {
+        Replicator {
+            events: primary.events(),
+            pending: Vec::new(),
+            follower_len,
+            dirty: None
+        }
+    }
+
+    /// Writes the messages that bring the follower up to date to out.
+    ///
+    /// If writing fails, nothing counts as shipped and the next call ships the same changes again.
+    pub fn ship(&mut self, primary: &mut FileObjStr, out: &mut impl Write) -> Result<(), Error> {
+        self.pending.extend(self.events.try_iter());
+
+        let count = self.write_changes(primary, out)?;
+
+        self.pending.clear();
+        self.follower_len = count;
+        self.dirty = None;
+        Ok(())
+    }
+
+    /// Writes the messages for the pending events and returns the object count the follower ends up with.
+    fn write_changes(&self, primary: &mut FileObjStr, out: &mut impl Write) -> Result<u64, Error> {
+        let mut follower_len = self.follower_len;
+        let mut dirty = self.dirty;
+
+        for event in &self.pending {
+            match *event {
+                Event::Append { .. } => {},
+                Event::Overwrite { ref range, .. } => mark(&mut dirty, range.start),
+                Event::Cut { len } => mark(&mut dirty, len),
+                Event::Rewrite { .. } => mark(&mut dirty, 0),
+                Event::Trim { objs } => {
+                    // Objects the follower never got are trimmed by shipping the frames after them.
+                    let trimmed = objs.min(follower_len);
+                    if trimmed > 0 {
+                        write_message(out, TRIM, trimmed, 0, &[])?;
+                    }
+                    follower_len -= trimmed;
+                    dirty = dirty.map(|dirty| dirty.saturating_sub(objs));
+                }
+            }
+        }
+
+        let count = primary.count();
+        let mut from = dirty
+            .map_or(follower_len, |dirty| dirty.min(follower_len))
+            .min(count);
+
+        let mut file = File::open(primary.path())?;
+        let end = stream_len(&mut file);
+
+        jump_stream_start(&mut file);
+        seek_forward_n(&mut file, from)?;
+        let mut prev = prev_crc(&mut file, from)?;
+
+        loop {
+            let start = position(&mut file);
+            let mut last = start;
+            let mut n = 0;
+            while from + n < count && position(&mut file) - start < BATCH_LEN {
+                last = position(&mut file);
+                seek_forward(&mut file)?;
+                n += 1;
+            }
+
+            let mut frames = vec![0; (position(&mut file) - start) as usize];
+            file.read_at(start, &mut frames)?;
+
+            if n > 0 || from < follower_len {
+                write_message(out, FRAMES, from, prev, &frames)?;
+            }
+
+            if n > 0 {
+                prev = crc32c(&frames[(last - start) as usize..]);
+            }
+
+            from += n;
+            if n == 0 || position(&mut file) >= end {
+                break;
+            }
+        }
+
+        out.flush()?;
+        Ok(count)
+    }
+
+    /// Ships the changes of primary straight to a follower in the same process.
+    pub fn replicate_to(&mut self, primary: &mut FileObjStr, follower: &mut FileObjStr) -> Result<(), Error> {
+        let mut buf = Vec::new();
+        self.ship(primary, &mut buf)?;
+        apply(follower, &mut buf.as_slice())?;
+        Ok(())
+    }
+}
+
+/// Marks the objects from index on as changed.
+fn mark(dirty: &mut Option<u64>, index: u64) {
+    *dirty = Some(dirty.map_or(index, |dirty| dirty.min(index)));
+}
+
+/// Applies the messages read from input to follower until input ends and returns how many were applied.
+///
+/// Every frame is verified by its mirrored lengths and metadata before anything is written,
+/// a message that does not fit the current follower fails without changing it.
+pub fn apply(follower: &mut FileObjStr, input: &mut impl Read) -> Result<u64, Error> {
+    let mut applied = 0;
+
+    while let Some(Message { kind, index, prev, body }) = read_message(input)? {
+        match kind {
+            FRAMES => {
+                let n = verify_frames(&body)?;
+                let count = follower.count();
+
+                if index > count {
+                    return Err(invalid("Follower is missing objects before the shipped frames."));
+                }
+
+                let mut file = File::open(follower.path())?;
+                jump_stream_start(&mut file);
+                seek_forward_n(&mut file, index)?;
+                if prev_crc(&mut file, index)? != prev {
+                    return Err(invalid("Follower diverged from the primary."));
+                }
+
+                if index < count {
+                    follower.seek(SeekFrom::Start(index))?;
+                    follower.cut()?;
+                }
+                follower.append_frames(body, n)?;
+            },
+            TRIM => follower.trim_front(index)?,
+            _ => return Err(invalid("Unknown replication message."))
+        }
+        applied += 1;
+    }
+
+    Ok(applied)
+}
+
+/// Checks the frames of a message body and returns how many it holds.
+fn verify_frames(body: &[u8]) -> Result<u64, Error> {
+    let mut pos = 0;
+    let mut n = 0;
+
+    while pos < body.len() {
+        let (len, has_meta) = decode_head(&body[pos..]).ok_or_else(|| invalid("Truncated frame."))?;
+
+        let inner = pos + OP_LEN as usize;
+        let next = inner + len as usize + OP_LEN as usize;
+
+        if body.get(next - OP_LEN as usize..next) != Some(&body[pos..inner]) {
+            return Err(invalid("Frame lengths do not match."));
+        }
+
+        if has_meta {
+            let meta = meta_range(&body[inner..], len as usize)?;
+            Meta::decode(&body[inner..][meta])?;
+        }
+
+        pos = next;
+        n += 1;
+    }
+
+    Ok(n)
+}
+
+/// CRC of the raw frame before the one at the file position, 0 at the stream start.
+fn prev_crc(file: &mut File, index: u64) -> Result<u32, Error> {
+    if index == 0 {
+        return Ok(0);
+    }
+
+    let pos = position(file);
+    let start = stream::prev(file, pos)?;
+
+    let mut frame = vec![0; (pos - start) as usize];
+    file.read_at(start, &mut frame)?;
+
+    Ok(crc32c(&frame))
+}
+
+fn write_message(out: &mut impl Write, kind: u8, index: u64, prev: u32, body: &[u8]) -> Result<(), Error> {
+    let len = u32::try_from(body.len())
+        .ok()
+        .filter(|&len| len as u64 <= MAX_BODY_LEN)
+        .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "Message is too long."))?;
+
+    let mut head = Vec::with_capacity(21);
+    head.push(kind);
+    head.extend_from_slice(&u64::to_be_bytes(index));
+    head.extend_from_slice(&u32::to_be_bytes(prev));
+    head.extend_from_slice(&u32::to_be_bytes(crc32c(body)));
+    head.extend_from_slice(&u32::to_be_bytes(len));
+
+    out.write_all(&head)?;
+    out.write_all(body)
+}
+
+/// Reads the next message, None if input ended between messages.
+///
+/// The body buffer grows with the bytes actually read, so a corrupt length can not allocate more than input holds.
+fn read_message(input: &mut impl Read) -> Result<Option<Message>, Error> {
+    let mut head = [0; 21];
+
+    match input.read_exact(&mut head[..1]) {
+        Ok(()) => {},
+        Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e)
+    }
+    input.read_exact(&mut head[1..])?;
+
+    let index = u64::from_be_bytes(head[1..9].try_into().unwrap());
+    let prev = u32::from_be_bytes(head[9..13].try_into().unwrap());
+    let crc = u32::from_be_bytes(head[13..17].try_into().unwrap());
+    let len = u32::from_be_bytes(head[17..21].try_into().unwrap());
+
+    if len as u64 > MAX_BODY_LEN {
+        return Err(invalid("Message is too long."));
+    }
+
+    let mut body = Vec::new();
+    input.take(len as u64).read_to_end(&mut body)?;
+    if body.len() != len as usize {
+        return Err(Error::new(ErrorKind::UnexpectedEof, "Message is truncated."));
+    }
+
+    if crc32c(&body) != crc {
+        return Err(invalid("Message checksum mismatch."));
+    }
+
+    Ok(Some(Message { kind: head[0], index, prev, body }))
+}
+
+fn invalid(msg: &'static str) -> Error {
+    Error::new(ErrorKind::InvalidData, msg)
+}
@@ -0,0 +1,115 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::api::Meta;
+
+/// Extracts the creation time of an object from its payload.
+pub type Timestamp = fn(&[u8]) -> Option<SystemTime>;
+
+/// Slack of a new retention in percent.
+const DEFAULT_SLACK: u32 = 10;
+
+/// Limits that decide which objects at the front of a stream are expired.
+///
+/// A limit is only enforced once it is exceeded by more than `slack` percent, 10 by default,
+/// then the stream is trimmed back to the limit itself.
+/// This way the stream is not rewritten on every single append.
+#[derive(Debug, Clone)]
+pub struct Retention {
+    pub(crate) max_objects: Option<u64>,
+    pub(crate) max_bytes: Option<u64>,
+    pub(crate) max_age: Option<Duration>,
+    pub(crate) timestamp: Option<Timestamp>,
+    pub(crate) slack: u32,
+}
+
+impl Default for Retention {
+    fn default() -> Retention {
+        Retention {
+            max_objects: None,
+            max_bytes: None,
+            max_age: None,
+            timestamp: None,
+            slack: DEFAULT_SLACK
+        }
+    }
+}
+
+impl Retention {
+    pub fn new() -> Retention {
+        Retention::default()
+    }
+
+    /// Keeps at most n objects.
+    pub fn max_objects(mut self, n: u64) -> Retention {
+        self.max_objects = Some(n);
+        self
+    }
+
+    /// Keeps at most n bytes of frames, the stream terminator is not counted.
+    pub fn max_bytes(mut self, n: u64) -> Retention {
+        self.max_bytes = Some(n);
+        self
+    }
+
+    /// Drops objects older than age.
+    ///
+    /// The creation timestamp of the object metadata is used, objects without one fall back to the timestamp function.
+    /// Objects without any timestamp never expire by age.
+    pub fn max_age(mut self, age: Duration) -> Retention {
+        self.max_age = Some(age);
+        self
+    }
+
+    /// Takes the timestamp of objects without a metadata creation timestamp from their payload.
+    pub fn timestamp(mut self, timestamp: Timestamp) -> Retention {
+        self.timestamp = Some(timestamp);
+        self
+    }
+
+    /// Percentage a limit may be exceeded before the stream is trimmed, rounded up to whole objects or bytes.
+    /// 
+    /// With 0, the stream is trimmed on every append once it is at a limit.
+    pub fn slack(mut self, percent: u32) -> Retention {
+        self.slack = percent;
+        self
+    }
+
+    pub(crate) fn over_objects(&self, count: u64, slack: bool) -> bool {
+        self.max_objects.is_some_and(|max| count > self.limit(max, slack))
+    }
+
+    pub(crate) fn over_bytes(&self, bytes: u64, slack: bool) -> bool {
+        self.max_bytes.is_some_and(|max| bytes > self.limit(max, slack))
+    }
+
+    pub(crate) fn expired(&self, data: &[u8], meta: Option<&Meta>, now: SystemTime, slack: bool) -> bool {
+        let Some(age) = self.max_age else {
+            return false;
+        };
+
+        let timestamp = meta
+            .and_then(|meta| meta.created)
+            .map(|created| UNIX_EPOCH + created)
+            .or_else(|| self.timestamp.and_then(|timestamp| timestamp(data)));
+
+        let Some(timestamp) = timestamp else {
+            return false;
+        };
+
+        let age = if slack {
+            age + age * self.slack / 100
+        } else {
+            age
+        };
+
+        now.duration_since(timestamp).is_ok_and(|elapsed| elapsed > age)
+    }
+
+    fn limit(&self, max: u64, slack: bool) -> u64 {
+        if slack {
+            max + (max * self.slack as u64).div_ceil(100)
+        } else {
+            max
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use std::{fs::File, io::{Error, ErrorKind}, mem, ops::Range, path::Path};
+
+use crate::frame::{stream, Storage as _};
+
+use super::{helpers::{frame_end, sync_frame, EMPTY, OP_LEN}, FileObjStr};
+
+/// Outcome of a salvage run.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Salvage {
+    /// Number of objects written to the new stream.
+    pub recovered: u64,
+    /// Byte ranges of the damaged file that did not belong to an intact frame.
+    pub lost: Vec<Range<u64>>,
+    /// Byte ranges of two or more consecutive empty objects.
+    /// 
+    /// A zero-filled region reads as a run of empty frames, so these objects were recovered but may stem from damage.
+    pub suspicious: Vec<Range<u64>>
+}
+
+/// Recovers every intact frame of a damaged stream at src into a new stream at dest.
+/// 
+/// Frames are verified by their mirrored lengths and metadata section.
+/// After damage, the scan resynchronizes on the next valid frame boundary.
+/// Runs of empty objects are recovered, but reported as suspicious, since zeroed bytes read as empty frames.
+pub fn salvage(src: &Path, dest: &Path) -> Result<Salvage, Error> {
+    if dest.exists() {
+        return Err(
+            Error::new(
+                ErrorKind::AlreadyExists,
+                "Salvage destination already exists."
+            )
+        );
+    }
+
+    let mut file = File::open(src)?;
+    let mut out = FileObjStr::new(dest)?;
+    let mut report = Salvage::default();
+
+    let mut end = file.metadata()?.len();
+    if end > 0 {
+        let mut last = [0; 1];
+        file.read_at(end - 1, &mut last)?;
+        if last == EMPTY {
+            end -= 1;
+        }
+    }
+
+    let mut pos = 0;
+    let mut zeros = 0..0;
+    while pos < end {
+        if let Some(frame) = intact_frame(&mut file, pos, end)? {
+            let next = pos + frame.len() as u64;
+
+            if frame.len() == 2 * OP_LEN as usize {
+                if zeros.is_empty() {
+                    zeros.start = pos;
+                }
+                zeros.end = next;
+            } else {
+                report.flag_zeros(mem::take(&mut zeros));
+            }
+
+            pos = next;
+            out.append_frames(frame, 1)?;
+            report.recovered += 1;
+            continue;
+        }
+
+        report.flag_zeros(mem::take(&mut zeros));
+
+        let next = sync_frame(&mut file, pos + 1, end)?.unwrap_or(end);
+        report.lost.push(pos..next);
+        pos = next;
+    }
+    report.flag_zeros(zeros);
+
+    Ok(report)
+}
+
+impl Salvage {
+    /// Reports the run of empty frames in zeros as suspicious, if it holds more than one frame.
+    fn flag_zeros(&mut self, zeros: Range<u64>) {
+        if zeros.end - zeros.start > 2 * OP_LEN as u64 {
+            self.suspicious.push(zeros);
+        }
+    }
+}
+
+/// Returns the raw bytes of the frame at pos, if its lengths agree and its metadata decodes.
+fn intact_frame(file: &mut File, pos: u64, end: u64) -> Result<Option<Vec<u8>>, Error> {
+    let Some(next) = frame_end(file, pos, end)? else {
+        return Ok(None);
+    };
+
+    match stream::read_meta(file, pos) {
+        Ok(_) => (),
+        Err(e) if e.kind() == ErrorKind::InvalidData => return Ok(None),
+        Err(e) => return Err(e)
+    }
+
+    let mut frame = vec![0; (next - pos) as usize];
+    file.read_at(pos, &mut frame)?;
+
+    Ok(Some(frame))
+}
@@ -0,0 +1,145 @@
+use std::{fs::File, io::{Error, ErrorKind, Read as _, SeekFrom}, path::Path, sync::{atomic::{AtomicU64, Ordering}, Arc}};
+
+use crate::api::Meta;
+
+use super::helpers::{jump, jump_stream_start, position, read_with_meta, seek_backward_n, seek_forward, OP_LEN};
+
+/// Read-only view of a stream pinned to the objects it held when the snapshot was taken.
+///
+/// Objects appended afterwards are not visible. If the stream is cut, overwritten, patched or trimmed
+/// through the `FileObjStr` the snapshot was taken from, every further access fails with an error,
+/// even if the stream was regrown to the same length. Cuts by other handles are only detected
+/// if they leave the stream shorter or change the last length field of the pinned objects.
+pub struct Snapshot {
+    file: File,
+    end: u64,
+    count: u64,
+    tail: [u8; OP_LEN as usize],
+    generation: Arc<AtomicU64>,
+    pinned: u64
+}
+
+impl Snapshot {
+    pub(crate) fn new(path: &Path, end: u64, count: u64, generation: Arc<AtomicU64>) -> Result<Snapshot, Error> {
+        let pinned = generation.load(Ordering::SeqCst);
+        let mut snapshot = Snapshot {
+            file: File::open(path)?,
+            end,
+            count,
+            tail: [0; OP_LEN as usize],
+            generation,
+            pinned
+        };
+
+        snapshot.tail = snapshot.read_tail()?;
+        jump_stream_start(&mut snapshot.file);
+
+        Ok(snapshot)
+    }
+
+    /// Returns the number of objects pinned by the snapshot.
+    pub fn object_count(&self) -> u64 {
+        self.count
+    }
+
+    /// Seeks to the given object in the snapshot.
+    pub fn seek(&mut self, pos: SeekFrom) -> Result<(), Error> {
+        self.check()?;
+
+        match pos {
+            SeekFrom::Start(pos) => {
+                jump_stream_start(&mut self.file);
+                self.seek_forward_n(pos)
+            },
+            SeekFrom::End(pos) => {
+                jump(&mut self.file, self.end);
+                seek_backward_n(&mut self.file, pos.unsigned_abs())
+            },
+            SeekFrom::Current(pos) => {
+                if pos < 0 {
+                    seek_backward_n(&mut self.file, pos.unsigned_abs())
+                } else {
+                    self.seek_forward_n(pos as u64)
+                }
+            }
+        }
+    }
+
+    /// Read the next object from the snapshot.
+    pub fn read(&mut self) -> Result<Vec<u8>, Error> {
+        Ok(self.read_with_meta()?.0)
+    }
+
+    /// Read the next object together with its metadata from the snapshot.
+    pub fn read_with_meta(&mut self) -> Result<(Vec<u8>, Option<Meta>), Error> {
+        self.check()?;
+
+        if position(&mut self.file) >= self.end {
+            return Err(end_reached());
+        }
+
+        read_with_meta(&mut self.file)
+    }
+
+    fn seek_forward_n(&mut self, n: u64) -> Result<(), Error> {
+        for _ in 0..n {
+            if position(&mut self.file) >= self.end {
+                jump(&mut self.file, self.end);
+                return Err(end_reached());
+            }
+            seek_forward(&mut self.file)?;
+        }
+        Ok(())
+    }
+
+    fn check(&mut self) -> Result<(), Error> {
+        if self.generation.load(Ordering::SeqCst) != self.pinned {
+            return Err(
+                Error::other("Snapshot was invalidated by a modification.")
+            );
+        }
+
+        let len = self.file.metadata()?.len();
+
+        if len <= self.end || self.read_tail()? != self.tail {
+            return Err(
+                Error::other("Snapshot was invalidated by a cut.")
+            );
+        }
+
+        Ok(())
+    }
+
+    fn read_tail(&mut self) -> Result<[u8; OP_LEN as usize], Error> {
+        let mut tail = [0; OP_LEN as usize];
+
+        if self.end >= OP_LEN as u64 {
+            let pos = position(&mut self.file);
+            jump(&mut self.file, self.end - OP_LEN as u64);
+            let res = self.file.read_exact(&mut tail);
+            jump(&mut self.file, pos);
+            res?;
+        }
+
+        Ok(tail)
+    }
+}
+
+impl Iterator for Snapshot {
+    type Item = Result<Vec<u8>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.read() {
+            Ok(data) => Some(Ok(data)),
+            Err(e) if e.kind() == ErrorKind::UnexpectedEof => None,
+            Err(e) => Some(Err(e))
+        }
+    }
+}
+
+fn end_reached() -> Error {
+    Error::new(
+        ErrorKind::UnexpectedEof,
+        "Reached snapshot end."
+    )
+}
@@ -0,0 +1,25 @@
+use crate::io::Error;
+
+/// Value every byte of an erased block reads as.
+pub const ERASED: u8 = 0xff;
+
+/// Flash like device made of fixed-size erase blocks.
+/// 
+/// Programming can only clear bits, so bytes have to be erased before they are written again.
+pub trait BlockDevice {
+
+    /// Size of an erase block in bytes.
+    fn block_size(&self) -> usize;
+
+    /// Number of erase blocks of the device.
+    fn block_count(&self) -> u64;
+
+    /// Fills buf with the bytes of block starting at offset.
+    fn read_block(&mut self, block: u64, offset: usize, buf: &mut [u8]) -> Result<(), Error>;
+
+    /// Programs data into block starting at offset.
+    fn write_block(&mut self, block: u64, offset: usize, data: &[u8]) -> Result<(), Error>;
+
+    /// Erases block, so all its bytes read as `ERASED`.
+    fn erase_block(&mut self, block: u64) -> Result<(), Error>;
+}
@@ -0,0 +1,365 @@
+use alloc::{vec, vec::Vec};
+
+use crate::{api::{Meta, ObjStr}, io::{Error, ErrorKind, SeekFrom}};
+
+use super::{decode_head, encode_frame, stream, BlockDevice, Storage, EMPTY, ERASED, HEAD_LEN, OP_LEN};
+
+/// Length of the header every used block starts with, `[u32 page][u32 sequence number]`.
+const BLOCK_HEAD_LEN: usize = 8;
+
+/// Object stream laid out across the erase blocks of a flash device.
+/// 
+/// Every used block starts with a header naming the page of the stream it holds and a sequence number,
+/// the frames are written back to back across the pages, frames may span pages.
+/// Instead of a terminator byte the stream ends at the first erased length field,
+/// so appending only programs erased bytes and never erases a block.
+/// 
+/// A page whose bytes can not be programmed in place is copied into a spare block,
+/// whose header is programmed last and replaces the old block, which is erased afterwards.
+/// A power loss during a rewrite thus leaves either the old or the new page, one block of the device is kept spare for this.
+pub struct BlockObjStr<D: BlockDevice> {
+    device: D,
+    page_size: u64,
+    capacity: u64,
+    pages: Vec<Option<(u64, u32)>>,
+    end: u64,
+    pos: u64
+}
+
+impl<D: BlockDevice> BlockObjStr<D> {
+    /// Opens the stream on device by reading its block headers and walking its frames, an erased device holds an empty stream.
+    /// 
+    /// Blocks left over by an interrupted rewrite are erased.
+    pub fn new(device: D) -> Result<BlockObjStr<D>, Error> {
+        let block_size = device.block_size();
+        let blocks = device.block_count();
+
+        if block_size <= BLOCK_HEAD_LEN || blocks > u32::MAX as u64 {
+            return Err(Error::new(ErrorKind::InvalidInput, "Device geometry is not supported."));
+        }
+
+        let page_size = (block_size - BLOCK_HEAD_LEN) as u64;
+        let page_count = blocks.saturating_sub(1);
+
+        let mut str = BlockObjStr {
+            device,
+            page_size,
+            capacity: page_size * page_count,
+            pages: vec![None; page_count as usize],
+            end: 0,
+            pos: 0
+        };
+
+        for block in 0..blocks {
+            str.mount(block)?;
+        }
+
+        while let Some(len) = str.frame_len(str.end)? {
+            str.end = str.end.checked_add(len)
+                .filter(|&end| end <= str.capacity)
+                .ok_or_else(|| Error::new(ErrorKind::InvalidData, "Frame exceeds device."))?;
+        }
+
+        Ok(str)
+    }
+
+    /// Returns the device the stream is kept on.
+    pub fn into_inner(self) -> D {
+        self.device
+    }
+
+    /// Maps the page held by block, if its header names one.
+    /// 
+    /// Of two blocks holding the same page, the one with the newer sequence number is kept and the other one erased.
+    fn mount(&mut self, block: u64) -> Result<(), Error> {
+        let mut head = [0; BLOCK_HEAD_LEN];
+        self.device.read_block(block, 0, &mut head)?;
+
+        if head[..4] == [ERASED; 4] {
+            return self.erase_dirty(block);
+        }
+
+        let page = u32::from_be_bytes(head[..4].try_into().unwrap());
+        let seq = u32::from_be_bytes(head[4..].try_into().unwrap());
+
+        let slot = self.pages.get_mut(page as usize)
+            .ok_or_else(|| Error::new(ErrorKind::InvalidData, "Block header names no page of the device."))?;
+
+        match *slot {
+            Some((_, other_seq)) if (seq.wrapping_sub(other_seq) as i32) <= 0 => self.device.erase_block(block),
+            Some((other, _)) => {
+                *slot = Some((block, seq));
+                self.device.erase_block(other)
+            },
+            None => {
+                *slot = Some((block, seq));
+                Ok(())
+            }
+        }
+    }
+
+    /// Erases a block without header, unless it is erased already.
+    fn erase_dirty(&mut self, block: u64) -> Result<(), Error> {
+        let mut contents = vec![0; self.page_size as usize];
+        self.device.read_block(block, BLOCK_HEAD_LEN, &mut contents)?;
+
+        if contents.iter().any(|&b| b != ERASED) {
+            self.device.erase_block(block)?;
+        }
+        Ok(())
+    }
+
+    /// Returns a block that holds no page, the device always keeps one spare.
+    fn spare_block(&self) -> Result<u64, Error> {
+        let mut used = vec![false; self.device.block_count() as usize];
+        for (block, _) in self.pages.iter().flatten() {
+            used[*block as usize] = true;
+        }
+
+        used.iter().position(|used| !used)
+            .map(|block| block as u64)
+            .ok_or_else(|| Error::new(ErrorKind::InvalidData, "Device has no spare block."))
+    }
+
+    /// Length of the frame at pos, None if the stream ends at pos.
+    fn frame_len(&mut self, pos: u64) -> Result<Option<u64>, Error> {
+        if pos + OP_LEN as u64 > self.capacity {
+            return Ok(None);
+        }
+
+        let mut head_buf = [0; HEAD_LEN as usize];
+        let head_buf = &mut head_buf[..(self.capacity - pos).min(HEAD_LEN as u64) as usize];
+        self.read_at(pos, head_buf)?;
+
+        if head_buf[..OP_LEN as usize] == [ERASED; OP_LEN as usize] {
+            return Ok(None);
+        }
+
+        let (len, _) = decode_head(head_buf)
+            .ok_or_else(|| Error::new(ErrorKind::InvalidData, "Frame exceeds device."))?;
+        Ok(Some(stream::frame_len(len)))
+    }
+
+    /// The written part of the device as storage for the frame functions.
+    fn frames(&mut self) -> Frames<'_, D> {
+        Frames(self)
+    }
+
+    fn read_at(&mut self, mut pos: u64, mut buf: &mut [u8]) -> Result<(), Error> {
+        while !buf.is_empty() {
+            let (page, offset) = (pos / self.page_size, (pos % self.page_size) as usize);
+            let n = buf.len().min(self.page_size as usize - offset);
+
+            let (head, tail) = buf.split_at_mut(n);
+            match self.pages[page as usize] {
+                Some((block, _)) => self.device.read_block(block, BLOCK_HEAD_LEN + offset, head)?,
+                None => head.fill(ERASED)
+            }
+
+            buf = tail;
+            pos += n as u64;
+        }
+        Ok(())
+    }
+
+    /// Writes data at pos, pages are only rewritten if their bytes can not be programmed in place.
+    fn write_at(&mut self, mut pos: u64, mut data: &[u8]) -> Result<(), Error> {
+        while !data.is_empty() {
+            let (page, offset) = (pos / self.page_size, (pos % self.page_size) as usize);
+            let n = data.len().min(self.page_size as usize - offset);
+
+            let block = match self.pages[page as usize] {
+                Some((block, _)) => block,
+                None => {
+                    let block = self.spare_block()?;
+                    self.device.write_block(block, 0, &block_head(page, 0))?;
+                    self.pages[page as usize] = Some((block, 0));
+                    block
+                }
+            };
+
+            let mut old = vec![0; n];
+            self.device.read_block(block, BLOCK_HEAD_LEN + offset, &mut old)?;
+
+            if old.iter().zip(&data[..n]).all(|(old, new)| old & new == *new) {
+                self.device.write_block(block, BLOCK_HEAD_LEN + offset, &data[..n])?;
+            } else {
+                let mut contents = vec![0; self.page_size as usize];
+                self.device.read_block(block, BLOCK_HEAD_LEN, &mut contents)?;
+                contents[offset..offset + n].copy_from_slice(&data[..n]);
+                self.rewrite_page(page, &contents)?;
+            }
+
+            data = &data[n..];
+            pos += n as u64;
+        }
+        Ok(())
+    }
+
+    /// Erases the stream bytes from pos on.
+    fn erase_from(&mut self, pos: u64) -> Result<(), Error> {
+        if pos >= self.end {
+            return Ok(());
+        }
+
+        let mut page = pos / self.page_size;
+        let offset = (pos % self.page_size) as usize;
+
+        if offset > 0 {
+            let mut contents = vec![ERASED; self.page_size as usize];
+            self.read_at(page * self.page_size, &mut contents[..offset])?;
+            self.rewrite_page(page, &contents)?;
+            page += 1;
+        }
+
+        while page * self.page_size < self.end {
+            if let Some((block, _)) = self.pages[page as usize].take() {
+                self.device.erase_block(block)?;
+            }
+            page += 1;
+        }
+
+        Ok(())
+    }
+
+    /// Copies contents into the spare block, which replaces the block holding page once its header is programmed.
+    fn rewrite_page(&mut self, page: u64, contents: &[u8]) -> Result<(), Error> {
+        let spare = self.spare_block()?;
+        let old = self.pages[page as usize];
+        let seq = old.map_or(0, |(_, seq)| seq.wrapping_add(1));
+
+        let used = contents.iter().rposition(|&b| b != ERASED).map_or(0, |i| i + 1);
+        if used > 0 {
+            self.device.write_block(spare, BLOCK_HEAD_LEN, &contents[..used])?;
+        }
+        self.device.write_block(spare, 0, &block_head(page, seq))?;
+        self.pages[page as usize] = Some((spare, seq));
+
+        if let Some((block, _)) = old {
+            self.device.erase_block(block)?;
+        }
+        Ok(())
+    }
+
+    /// Reads the inner section of the next frame and moves the cursor behind it.
+    fn read_inner(&mut self) -> Result<(Vec<u8>, bool), Error> {
+        let pos = self.pos;
+        let mut inner = Vec::new();
+        let (has_meta, next) = stream::read_inner_into(&mut self.frames(), pos, &mut inner)?;
+        self.pos = next;
+        Ok((inner, has_meta))
+    }
+
+    fn encode(data: &[&[u8]]) -> Result<Vec<u8>, Error> {
+        let mut buf = Vec::new();
+        for d in data {
+            encode_frame(&mut buf, d, false)?;
+        }
+        Ok(buf)
+    }
+}
+
+impl<D: BlockDevice> ObjStr for BlockObjStr<D> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<(), Error> {
+        let mut next = match pos {
+            SeekFrom::Start(_) => 0,
+            SeekFrom::End(_) => self.end,
+            SeekFrom::Current(_) => self.pos
+        };
+
+        let res = match pos {
+            SeekFrom::End(n) | SeekFrom::Current(n) if n < 0 => stream::skip_backward(&mut self.frames(), &mut next, n.unsigned_abs()),
+            SeekFrom::End(n) => stream::skip_backward(&mut self.frames(), &mut next, n as u64),
+            SeekFrom::Start(n) => stream::skip_forward(&mut self.frames(), &mut next, n),
+            SeekFrom::Current(n) => stream::skip_forward(&mut self.frames(), &mut next, n as u64)
+        };
+
+        self.pos = next;
+        res
+    }
+
+    fn read(&mut self) -> Result<Vec<u8>, Error> {
+        let (mut inner, has_meta) = self.read_inner()?;
+        stream::strip_meta(&mut inner, has_meta)?;
+        Ok(inner)
+    }
+
+    fn read_meta(&mut self) -> Result<Option<Meta>, Error> {
+        Ok(self.read_with_meta()?.1)
+    }
+
+    fn read_with_meta(&mut self) -> Result<(Vec<u8>, Option<Meta>), Error> {
+        let (inner, has_meta) = self.read_inner()?;
+        stream::split_meta(inner, has_meta)
+    }
+
+    fn len(&mut self, contents: u8, objs: u8) -> Result<u64, Error> {
+        let pos = self.pos;
+        stream::inner_len(&mut self.frames(), pos, contents, objs)
+    }
+
+    fn overwrite(&mut self, data: &[&[u8]], objs: u8) -> Result<(), Error> {
+        let pos = self.pos;
+        self.pos = stream::overwrite(&mut self.frames(), pos, data, objs)?;
+        Ok(())
+    }
+
+    fn append(&mut self, data: &[u8]) -> Result<(), Error> {
+        let buf = Self::encode(&[data])?;
+
+        if self.end + buf.len() as u64 > self.capacity {
+            return Err(Error::other("Device is full."));
+        }
+
+        self.write_at(self.end, &buf)?;
+        self.end += buf.len() as u64;
+        Ok(())
+    }
+
+    fn cut(&mut self) -> Result<(), Error> {
+        self.erase_from(self.pos)?;
+        self.end = self.pos;
+        Ok(())
+    }
+}
+
+/// The frames of a block stream as storage, followed by the terminator the device does not hold.
+struct Frames<'a, D: BlockDevice>(&'a mut BlockObjStr<D>);
+
+impl<D: BlockDevice> Storage for Frames<'_, D> {
+    fn len(&mut self) -> Result<u64, Error> {
+        Ok(self.0.end + EMPTY.len() as u64)
+    }
+
+    fn read_at(&mut self, pos: u64, buf: &mut [u8]) -> Result<(), Error> {
+        if pos + buf.len() as u64 > self.len()? {
+            return Err(Error::new(ErrorKind::UnexpectedEof, "Read exceeds storage length."));
+        }
+
+        let n = (self.0.end.saturating_sub(pos) as usize).min(buf.len());
+        let (frames, terminator) = buf.split_at_mut(n);
+        self.0.read_at(pos, frames)?;
+        terminator.copy_from_slice(&EMPTY[..terminator.len()]);
+        Ok(())
+    }
+
+    fn write_at(&mut self, pos: u64, buf: &[u8]) -> Result<(), Error> {
+        if pos + buf.len() as u64 > self.0.end {
+            return Err(Error::new(ErrorKind::InvalidInput, "Write exceeds stream end."));
+        }
+        self.0.write_at(pos, buf)
+    }
+
+    fn truncate(&mut self, len: u64) -> Result<(), Error> {
+        self.0.erase_from(len)?;
+        self.0.end = self.0.end.min(len);
+        Ok(())
+    }
+}
+
+fn block_head(page: u64, seq: u32) -> [u8; BLOCK_HEAD_LEN] {
+    let mut head = [0; BLOCK_HEAD_LEN];
+    head[..4].copy_from_slice(&u32::to_be_bytes(page as u32));
+    head[4..].copy_from_slice(&u32::to_be_bytes(seq));
+    head
+}
@@ -0,0 +1,119 @@
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use crate::io::{Error, ErrorKind};
+
+pub const EMPTY: [u8; 1] = [0; 1];
+pub const OP_LEN: u8 = 4;
+
+/// Flag bit of the length fields of a frame with metadata.
+///
+/// A stream is a sequence of frames `[u32 len][inner][u32 len]` with mirrored big endian length fields,
+/// followed by a single zero terminator byte.
+/// Frames with metadata set the flag and start their inner section with `[META_MARKER][u32 meta len][meta]`,
+/// the payload follows.
+///
+/// Before metadata existed the flag bit was part of the length, so a flagged length field without the marker
+/// is read as the full length of such a legacy frame of 2 GiB or more. New frames are limited to `LEN_MASK` bytes.
+pub const META_FLAG: u32 = 1 << 31;
+pub const LEN_MASK: u32 = !META_FLAG;
+
+/// Format marker at the start of the inner section of a frame with metadata.
+pub const META_MARKER: [u8; OP_LEN as usize] = *b"OSM\x01";
+
+/// Bytes at the start of a frame needed by `decode_head`.
+pub const HEAD_LEN: u8 = 2 * OP_LEN;
+
+/// Decodes the head of the frame at the start of buf into the inner length and the metadata flag.
+///
+/// A length field with the flag bit set is only a frame with metadata if the metadata marker follows,
+/// otherwise it is the length of a legacy frame.
+/// If buf ends before this is decided, None is returned.
+pub fn decode_head(buf: &[u8]) -> Option<(u32, bool)> {
+    let len = u32::from_be_bytes(buf.get(..OP_LEN as usize)?.try_into().unwrap());
+
+    if len & META_FLAG == 0 {
+        return Some((len, false));
+    }
+
+    if buf.get(OP_LEN as usize..HEAD_LEN as usize)? == META_MARKER {
+        Some((len & LEN_MASK, true))
+    } else {
+        Some((len, false))
+    }
+}
+
+/// Appends the frame of inner to buf.
+///
+/// The inner section of a frame with metadata has to start with the metadata marker, see `encode_meta_inner`.
+pub fn encode_frame(buf: &mut Vec<u8>, inner: &[u8], has_meta: bool) -> Result<(), Error> {
+
+    if inner.len() > LEN_MASK as usize {
+        return Err(
+            Error::new(
+                ErrorKind::InvalidInput,
+                "Data length is too long."
+            )
+        );
+    }
+
+    if has_meta && !inner.starts_with(&META_MARKER) {
+        return Err(
+            Error::new(
+                ErrorKind::InvalidInput,
+                "Metadata marker is missing."
+            )
+        );
+    }
+
+    let mut len = inner.len() as u32;
+    if has_meta {
+        len |= META_FLAG;
+    }
+
+    let op = u32::to_be_bytes(len);
+
+    buf.extend_from_slice(&op);
+    buf.extend_from_slice(inner);
+    buf.extend_from_slice(&op);
+
+    Ok(())
+}
+
+/// Builds the inner section of a frame with metadata from the encoded metadata and the payload.
+pub fn encode_meta_inner(meta: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut inner = Vec::with_capacity(HEAD_LEN as usize + meta.len() + data.len());
+    inner.extend_from_slice(&META_MARKER);
+    inner.extend_from_slice(&u32::to_be_bytes(meta.len() as u32));
+    inner.extend_from_slice(meta);
+    inner.extend_from_slice(data);
+    inner
+}
+
+/// Returns the range of the metadata in the inner section of a frame with metadata.
+///
+/// Only the length of inner and its first `HEAD_LEN` bytes are looked at,
+/// so a prefix of the inner section is enough as long as inner_len is its full length.
+pub fn meta_range(prefix: &[u8], inner_len: usize) -> Result<Range<usize>, Error> {
+    let Some(meta_len_buf) = prefix.get(OP_LEN as usize..HEAD_LEN as usize).filter(|_| inner_len >= HEAD_LEN as usize) else {
+        return Err(
+            Error::new(
+                ErrorKind::InvalidData,
+                "Metadata length is missing."
+            )
+        );
+    };
+
+    let meta_len = u32::from_be_bytes(meta_len_buf.try_into().unwrap()) as usize;
+
+    if meta_len > inner_len - HEAD_LEN as usize {
+        return Err(
+            Error::new(
+                ErrorKind::InvalidData,
+                "Metadata length exceeds object length."
+            )
+        );
+    }
+
+    Ok(HEAD_LEN as usize..HEAD_LEN as usize + meta_len)
+}
@@ -0,0 +1,100 @@
+use alloc::{vec, vec::Vec};
+
+use crate::io::{Error, ErrorKind};
+
+use super::{BlockDevice, ERASED};
+
+/// Simulated NOR flash kept in memory.
+/// 
+/// Programming a bit from 0 back to 1 without erasing fails like it would on real flash,
+/// and erases are counted per block to observe wear.
+pub struct MemFlash {
+    block_size: usize,
+    data: Vec<u8>,
+    erases: Vec<u64>,
+    budget: Option<u64>
+}
+
+impl MemFlash {
+    /// Creates an erased device of block_count blocks with block_size bytes each.
+    pub fn new(block_size: usize, block_count: u64) -> MemFlash {
+        MemFlash {
+            block_size,
+            data: vec![ERASED; block_size * block_count as usize],
+            erases: vec![0; block_count as usize],
+            budget: None
+        }
+    }
+
+    /// Simulates a power loss after n more writes or erases, every further write or erase fails without effect.
+    /// 
+    /// None restores the power.
+    pub fn fail_after(&mut self, n: Option<u64>) {
+        self.budget = n;
+    }
+
+    /// Returns how often block was erased.
+    pub fn erase_count(&self, block: u64) -> u64 {
+        self.erases[block as usize]
+    }
+
+    /// Returns the raw contents of the device.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    fn range(&self, block: u64, offset: usize, len: usize) -> Result<core::ops::Range<usize>, Error> {
+        if block >= self.block_count() || offset + len > self.block_size {
+            return Err(Error::new(ErrorKind::InvalidInput, "Access exceeds block."));
+        }
+        let start = block as usize * self.block_size + offset;
+        Ok(start..start + len)
+    }
+
+    fn spend(&mut self) -> Result<(), Error> {
+        match self.budget.as_mut() {
+            Some(0) => Err(Error::other("Power lost.")),
+            Some(budget) => {
+                *budget -= 1;
+                Ok(())
+            },
+            None => Ok(())
+        }
+    }
+}
+
+impl BlockDevice for MemFlash {
+    fn block_size(&self) -> usize {
+        self.block_size
+    }
+
+    fn block_count(&self) -> u64 {
+        self.erases.len() as u64
+    }
+
+    fn read_block(&mut self, block: u64, offset: usize, buf: &mut [u8]) -> Result<(), Error> {
+        let range = self.range(block, offset, buf.len())?;
+        buf.copy_from_slice(&self.data[range]);
+        Ok(())
+    }
+
+    fn write_block(&mut self, block: u64, offset: usize, data: &[u8]) -> Result<(), Error> {
+        let range = self.range(block, offset, data.len())?;
+        self.spend()?;
+
+        if self.data[range.clone()].iter().zip(data).any(|(old, new)| old & new != *new) {
+            return Err(Error::new(ErrorKind::InvalidInput, "Programming requires an erase."));
+        }
+
+        self.data[range].copy_from_slice(data);
+        Ok(())
+    }
+
+    fn erase_block(&mut self, block: u64) -> Result<(), Error> {
+        let range = self.range(block, 0, self.block_size)?;
+        self.spend()?;
+        self.data[range].fill(ERASED);
+        self.erases[block as usize] += 1;
+        Ok(())
+    }
+}
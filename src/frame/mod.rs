@@ -0,0 +1,15 @@
+
+mod block_device;
+mod block_obj_str;
+mod codec;
+mod mem_flash;
+mod storage;
+mod storage_obj_str;
+pub(crate) mod stream;
+
+pub use block_device::{BlockDevice, ERASED};
+pub use block_obj_str::BlockObjStr;
+pub use codec::{decode_head, encode_frame, encode_meta_inner, meta_range, EMPTY, HEAD_LEN, LEN_MASK, META_FLAG, META_MARKER, OP_LEN};
+pub use mem_flash::MemFlash;
+pub use storage::Storage;
+pub use storage_obj_str::StorageObjStr;
@@ -0,0 +1,105 @@
+use alloc::vec::Vec;
+
+use crate::io::{Error, ErrorKind};
+
+/// Byte addressable storage a stream is kept in.
+pub trait Storage {
+
+    /// Returns the length of the storage in bytes.
+    fn len(&mut self) -> Result<u64, Error>;
+
+    /// Returns true if the storage holds no bytes.
+    fn is_empty(&mut self) -> Result<bool, Error> {
+        Ok(self.len()? == 0)
+    }
+
+    /// Fills buf with the bytes starting at pos.
+    /// 
+    /// If the storage ends before buf is filled, error occurs.
+    fn read_at(&mut self, pos: u64, buf: &mut [u8]) -> Result<(), Error>;
+
+    /// Writes buf starting at pos, growing the storage if needed.
+    fn write_at(&mut self, pos: u64, buf: &[u8]) -> Result<(), Error>;
+
+    /// Shrinks the storage to len bytes.
+    fn truncate(&mut self, len: u64) -> Result<(), Error>;
+}
+
+impl Storage for Vec<u8> {
+    fn len(&mut self) -> Result<u64, Error> {
+        Ok(Vec::len(self) as u64)
+    }
+
+    fn read_at(&mut self, pos: u64, buf: &mut [u8]) -> Result<(), Error> {
+        let start = usize::try_from(pos).unwrap_or(usize::MAX);
+        match start.checked_add(buf.len()) {
+            Some(end) if end <= Vec::len(self) => {
+                buf.copy_from_slice(&self[start..end]);
+                Ok(())
+            },
+            _ => Err(Error::new(ErrorKind::UnexpectedEof, "Read exceeds storage length."))
+        }
+    }
+
+    fn write_at(&mut self, pos: u64, buf: &[u8]) -> Result<(), Error> {
+        let start = usize::try_from(pos)
+            .map_err(|_| Error::new(ErrorKind::InvalidInput, "Position exceeds address space."))?;
+        let end = start + buf.len();
+
+        if end > Vec::len(self) {
+            self.resize(end, 0);
+        }
+
+        self[start..end].copy_from_slice(buf);
+        Ok(())
+    }
+
+    fn truncate(&mut self, len: u64) -> Result<(), Error> {
+        Vec::truncate(self, usize::try_from(len).unwrap_or(usize::MAX));
+        Ok(())
+    }
+}
+
+/// Reads and writes do not move the file cursor, so a file can be used as storage and as a stream at once.
+#[cfg(feature = "std")]
+impl Storage for std::fs::File {
+    fn len(&mut self) -> Result<u64, Error> {
+        Ok(self.metadata()?.len())
+    }
+
+    #[cfg(unix)]
+    fn read_at(&mut self, pos: u64, buf: &mut [u8]) -> Result<(), Error> {
+        std::os::unix::fs::FileExt::read_exact_at(self, buf, pos)
+    }
+
+    #[cfg(not(unix))]
+    fn read_at(&mut self, pos: u64, buf: &mut [u8]) -> Result<(), Error> {
+        use std::io::{Read as _, Seek as _, SeekFrom};
+
+        let current = self.stream_position()?;
+        self.seek(SeekFrom::Start(pos))?;
+        let res = self.read_exact(buf);
+        self.seek(SeekFrom::Start(current))?;
+        res
+    }
+
+    #[cfg(unix)]
+    fn write_at(&mut self, pos: u64, buf: &[u8]) -> Result<(), Error> {
+        std::os::unix::fs::FileExt::write_all_at(self, buf, pos)
+    }
+
+    #[cfg(not(unix))]
+    fn write_at(&mut self, pos: u64, buf: &[u8]) -> Result<(), Error> {
+        use std::io::{Seek as _, SeekFrom, Write as _};
+
+        let current = self.stream_position()?;
+        self.seek(SeekFrom::Start(pos))?;
+        let res = self.write_all(buf);
+        self.seek(SeekFrom::Start(current))?;
+        res
+    }
+
+    fn truncate(&mut self, len: u64) -> Result<(), Error> {
+        self.set_len(len)
+    }
+}
@@ -0,0 +1,122 @@
+use alloc::{vec, vec::Vec};
+
+use crate::{api::{Meta, ObjStr}, io::{Error, SeekFrom}};
+
+use super::{encode_frame, encode_meta_inner, stream, Storage, EMPTY};
+
+/// Object stream kept in any storage, using the same frame format as `FileObjStr`.
+/// 
+/// Only the core and alloc are required, so the format can be used without an operating system.
+pub struct StorageObjStr<S: Storage> {
+    storage: S,
+    pos: u64
+}
+
+impl<S: Storage> StorageObjStr<S> {
+    /// Opens the stream kept in storage, an empty storage is initialized with an empty stream.
+    pub fn new(mut storage: S) -> Result<StorageObjStr<S>, Error> {
+        if storage.is_empty()? {
+            storage.write_at(0, &EMPTY)?;
+        }
+
+        Ok(
+            StorageObjStr {
+                storage,
+                pos: 0
+            }
+        )
+    }
+
+    /// Returns the storage the stream is kept in.
+    pub fn into_inner(self) -> S {
+        self.storage
+    }
+
+    /// Reads the inner section of the next frame and moves the cursor behind it.
+    fn read_inner(&mut self) -> Result<(Vec<u8>, bool), Error> {
+        let mut inner = Vec::new();
+        let (has_meta, next) = stream::read_inner_into(&mut self.storage, self.pos, &mut inner)?;
+        self.pos = next;
+        Ok((inner, has_meta))
+    }
+
+    fn write_frame(&mut self, pos: u64, inner: &[u8], has_meta: bool) -> Result<u64, Error> {
+        let mut buf = Vec::new();
+        encode_frame(&mut buf, inner, has_meta)?;
+        self.storage.write_at(pos, &buf)?;
+        Ok(pos + buf.len() as u64)
+    }
+}
+
+impl<S: Storage> ObjStr for StorageObjStr<S> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<(), Error> {
+        match pos {
+            SeekFrom::Start(pos) => {
+                self.pos = 0;
+                stream::skip_forward(&mut self.storage, &mut self.pos, pos)
+            },
+            SeekFrom::End(pos) => {
+                self.pos = stream::end(&mut self.storage)?;
+                stream::skip_backward(&mut self.storage, &mut self.pos, pos.unsigned_abs())
+            },
+            SeekFrom::Current(pos) if pos < 0 => stream::skip_backward(&mut self.storage, &mut self.pos, pos.unsigned_abs()),
+            SeekFrom::Current(pos) => stream::skip_forward(&mut self.storage, &mut self.pos, pos as u64)
+        }
+    }
+
+    fn read(&mut self) -> Result<Vec<u8>, Error> {
+        let (mut inner, has_meta) = self.read_inner()?;
+        stream::strip_meta(&mut inner, has_meta)?;
+        Ok(inner)
+    }
+
+    fn read_range(&mut self, offset: u64, len: usize) -> Result<Vec<u8>, Error> {
+        let start = stream::payload_range(&mut self.storage, self.pos, offset, len as u64)?;
+        let mut data = vec![0; len];
+        self.storage.read_at(start, &mut data)?;
+        Ok(data)
+    }
+
+    fn read_meta(&mut self) -> Result<Option<Meta>, Error> {
+        let (meta, next) = stream::read_meta(&mut self.storage, self.pos)?;
+        self.pos = next;
+        Ok(meta)
+    }
+
+    fn read_with_meta(&mut self) -> Result<(Vec<u8>, Option<Meta>), Error> {
+        let (inner, has_meta) = self.read_inner()?;
+        stream::split_meta(inner, has_meta)
+    }
+
+    fn len(&mut self, contents: u8, objs: u8) -> Result<u64, Error> {
+        stream::inner_len(&mut self.storage, self.pos, contents, objs)
+    }
+
+    fn overwrite(&mut self, data: &[&[u8]], objs: u8) -> Result<(), Error> {
+        self.pos = stream::overwrite(&mut self.storage, self.pos, data, objs)?;
+        Ok(())
+    }
+
+    fn patch(&mut self, offset: u64, data: &[u8]) -> Result<(), Error> {
+        let start = stream::payload_range(&mut self.storage, self.pos, offset, data.len() as u64)?;
+        self.storage.write_at(start, data)
+    }
+
+    fn append(&mut self, data: &[u8]) -> Result<(), Error> {
+        let end = stream::end(&mut self.storage)?;
+        let end = self.write_frame(end, data, false)?;
+        self.storage.write_at(end, &EMPTY)
+    }
+
+    fn append_with_meta(&mut self, data: &[u8], meta: &Meta) -> Result<(), Error> {
+        let inner = encode_meta_inner(&meta.encode()?, data);
+        let end = stream::end(&mut self.storage)?;
+        let end = self.write_frame(end, &inner, true)?;
+        self.storage.write_at(end, &EMPTY)
+    }
+
+    fn cut(&mut self) -> Result<(), Error> {
+        self.storage.truncate(self.pos)?;
+        self.storage.write_at(self.pos, &EMPTY)
+    }
+}
@@ -0,0 +1,267 @@
+use alloc::{vec, vec::Vec};
+use core::ops::Range;
+
+use crate::{api::Meta, io::{Error, ErrorKind}};
+
+use super::{decode_head, encode_frame, meta_range, Storage, EMPTY, HEAD_LEN, LEN_MASK, META_FLAG, OP_LEN};
+
+/// Position of the stream terminator.
+pub fn end<S: Storage + ?Sized>(storage: &mut S) -> Result<u64, Error> {
+    Ok(storage.len()?.saturating_sub(EMPTY.len() as u64))
+}
+
+/// Inner sections up to this many bytes are read without checking the storage length first.
+const CHECKED_LEN: u32 = 64 * 1024;
+
+/// Byte length of a frame with an inner section of len bytes.
+pub fn frame_len(len: u32) -> u64 {
+    len as u64 + 2 * OP_LEN as u64
+}
+
+/// Length field of a frame with an inner section of len bytes.
+pub fn raw_len(len: u32, has_meta: bool) -> u32 {
+    if has_meta {
+        len | META_FLAG
+    } else {
+        len
+    }
+}
+
+/// Decodes the head of the frame at pos into the inner length and the metadata flag.
+///
+/// If the stream ends at pos, error occurs.
+pub fn head<S: Storage + ?Sized>(storage: &mut S, pos: u64) -> Result<(u32, bool), Error> {
+    let mut head_buf = [0; HEAD_LEN as usize];
+    storage.read_at(pos, &mut head_buf).map_err(|e| match e.kind() {
+        ErrorKind::UnexpectedEof => end_reached(),
+        _ => e
+    })?;
+    Ok(decode_head(&head_buf).unwrap())
+}
+
+/// Returns the start of the frame after the one at pos, end is the position of the stream terminator.
+pub fn next<S: Storage + ?Sized>(storage: &mut S, pos: u64, end: u64) -> Result<u64, Error> {
+    if pos >= end {
+        return Err(end_reached());
+    }
+
+    let (len, _) = head(storage, pos)?;
+    let next = pos + frame_len(len);
+
+    if next > end {
+        return Err(invalid("Frame exceeds stream end."));
+    }
+
+    Ok(next)
+}
+
+/// Returns the start of the frame that ends at pos.
+pub fn prev<S: Storage + ?Sized>(storage: &mut S, pos: u64) -> Result<u64, Error> {
+    if pos == 0 {
+        return Err(Error::new(ErrorKind::UnexpectedEof, "Reached stream start."));
+    }
+
+    let right = pos.checked_sub(OP_LEN as u64).ok_or_else(|| invalid("Frame exceeds stream start."))?;
+    let mut len_buf = [0; OP_LEN as usize];
+    storage.read_at(right, &mut len_buf)?;
+    let len = u32::from_be_bytes(len_buf);
+
+    // A flagged length field is a frame with metadata if its head agrees, otherwise a legacy frame.
+    if len & META_FLAG != 0 {
+        if let Some(start) = pos.checked_sub(frame_len(len & LEN_MASK)) {
+            if head(storage, start)? == (len & LEN_MASK, true) {
+                return Ok(start);
+            }
+        }
+    }
+
+    pos.checked_sub(frame_len(len)).ok_or_else(|| invalid("Frame exceeds stream start."))
+}
+
+/// Moves pos n frames forward.
+///
+/// If the stream ends first, pos is left at the stream terminator and error occurs.
+pub fn skip_forward<S: Storage + ?Sized>(storage: &mut S, pos: &mut u64, n: u64) -> Result<(), Error> {
+    let end = end(storage)?;
+
+    for _ in 0..n {
+        match next(storage, *pos, end) {
+            Ok(next) => *pos = next,
+            Err(e) => {
+                if e.kind() == ErrorKind::UnexpectedEof {
+                    *pos = end;
+                }
+                return Err(e);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Moves pos n frames backward, if the stream starts first, error occurs.
+pub fn skip_backward<S: Storage + ?Sized>(storage: &mut S, pos: &mut u64, n: u64) -> Result<(), Error> {
+    for _ in 0..n {
+        *pos = prev(storage, *pos)?;
+    }
+    Ok(())
+}
+
+/// Reads the inner section of the frame at pos into buf and returns its metadata flag and the start of the next frame.
+///
+/// If the mirrored lengths of the frame disagree, error occurs.
+pub fn read_inner_into<S: Storage + ?Sized>(storage: &mut S, pos: u64, buf: &mut Vec<u8>) -> Result<(bool, u64), Error> {
+    let (len, has_meta) = head(storage, pos)?;
+    check_fits(storage, pos, len)?;
+
+    buf.clear();
+    buf.resize(len as usize + OP_LEN as usize, 0);
+    storage.read_at(pos + OP_LEN as u64, buf).map_err(exceeds_end)?;
+
+    let len_buf_right = buf[len as usize..].try_into().unwrap();
+    if u32::from_be_bytes(len_buf_right) != raw_len(len, has_meta) {
+        return Err(invalid("Data length mismatch."));
+    }
+
+    buf.truncate(len as usize);
+    Ok((has_meta, pos + frame_len(len)))
+}
+
+/// Reads only the metadata of the frame at pos and returns it with the start of the next frame.
+pub fn read_meta<S: Storage + ?Sized>(storage: &mut S, pos: u64) -> Result<(Option<Meta>, u64), Error> {
+    let (len, has_meta) = head(storage, pos)?;
+    let next = pos + frame_len(len);
+
+    if !has_meta {
+        return Ok((None, next));
+    }
+
+    check_fits(storage, pos, len)?;
+    let range = meta_range_at(storage, pos, len)?;
+    let mut meta = vec![0; range.len()];
+    storage.read_at(pos + OP_LEN as u64 + range.start as u64, &mut meta).map_err(exceeds_end)?;
+
+    Ok((Some(Meta::decode(&meta)?), next))
+}
+
+/// Returns the offset of the payload in the inner section of a frame, behind its metadata section if it has one.
+pub fn payload_start(inner: &[u8], has_meta: bool) -> Result<usize, Error> {
+    if !has_meta {
+        return Ok(0);
+    }
+
+    Ok(meta_range(inner, inner.len())?.end)
+}
+
+/// Drops the metadata section from the inner section in buf, leaving the payload.
+pub fn strip_meta(buf: &mut Vec<u8>, has_meta: bool) -> Result<(), Error> {
+    let start = payload_start(buf, has_meta)?;
+    buf.drain(..start);
+    Ok(())
+}
+
+/// Splits an inner section into the payload and the decoded metadata.
+pub fn split_meta(mut inner: Vec<u8>, has_meta: bool) -> Result<(Vec<u8>, Option<Meta>), Error> {
+    if !has_meta {
+        return Ok((inner, None));
+    }
+
+    let range = meta_range(&inner, inner.len())?;
+    let meta = Meta::decode(&inner[range.clone()])?;
+    let data = inner.split_off(range.end);
+
+    Ok((data, Some(meta)))
+}
+
+/// Returns the storage position and the length of the payload of the frame at pos.
+pub fn payload_span<S: Storage + ?Sized>(storage: &mut S, pos: u64) -> Result<(u64, u64), Error> {
+    let (inner, has_meta) = head(storage, pos)?;
+    let mut start = pos + OP_LEN as u64;
+    let mut payload_len = inner as u64;
+
+    if has_meta {
+        let skip = meta_range_at(storage, pos, inner)?.end as u64;
+        start += skip;
+        payload_len -= skip;
+    }
+
+    Ok((start, payload_len))
+}
+
+/// Returns the storage position of offset in the payload of the frame at pos,
+/// if len bytes from there fit into the payload.
+pub fn payload_range<S: Storage + ?Sized>(storage: &mut S, pos: u64, offset: u64, len: u64) -> Result<u64, Error> {
+    let (start, payload_len) = payload_span(storage, pos)?;
+
+    if offset.checked_add(len).is_none_or(|end| end > payload_len) {
+        return Err(Error::new(ErrorKind::InvalidInput, "Range exceeds object length."));
+    }
+
+    Ok(start + offset)
+}
+
+/// Returns the length of the objs frames from pos on minus the length fields of contents frames.
+///
+/// This is `ObjStr::len` for the object at pos.
+pub fn inner_len<S: Storage + ?Sized>(storage: &mut S, pos: u64, contents: u8, objs: u8) -> Result<u64, Error> {
+    let mut next = pos;
+    skip_forward(storage, &mut next, objs as u64)?;
+
+    (next - pos).checked_sub(2 * OP_LEN as u64 * contents as u64)
+        .ok_or_else(|| Error::other("To small to fit that many objects"))
+}
+
+/// Replaces the objs frames from pos on with frames of data of the same total length and returns the end of the written frames.
+///
+/// This is `ObjStr::overwrite` for the object at pos.
+pub fn overwrite<S: Storage + ?Sized>(storage: &mut S, pos: u64, data: &[&[u8]], objs: u8) -> Result<u64, Error> {
+    if data.len() > u8::MAX as usize {
+        return Err(Error::other("To many objects"));
+    }
+
+    let inner_len = inner_len(storage, pos, data.len() as u8, objs)?;
+    let actual_len = data.iter().fold(0, |acc, x| acc + x.len()) as u64;
+
+    if inner_len != actual_len {
+        return Err(Error::other("Wrong length"));
+    }
+
+    let mut buf = Vec::new();
+    for d in data {
+        encode_frame(&mut buf, d, false)?;
+    }
+
+    storage.write_at(pos, &buf)?;
+    Ok(pos + buf.len() as u64)
+}
+
+/// Returns the range of the metadata in the inner section of the frame with metadata at pos, whose inner section is len bytes.
+pub fn meta_range_at<S: Storage + ?Sized>(storage: &mut S, pos: u64, len: u32) -> Result<Range<usize>, Error> {
+    let mut prefix = [0; HEAD_LEN as usize];
+    let prefix = &mut prefix[..(len as usize).min(HEAD_LEN as usize)];
+    storage.read_at(pos + OP_LEN as u64, prefix).map_err(exceeds_end)?;
+    meta_range(prefix, len as usize)
+}
+
+/// Fails before a buffer for the inner section of the frame at pos is allocated, if a large frame exceeds the storage.
+fn check_fits<S: Storage + ?Sized>(storage: &mut S, pos: u64, len: u32) -> Result<(), Error> {
+    if len > CHECKED_LEN && pos + frame_len(len) > storage.len()? {
+        return Err(invalid("Frame exceeds stream end."));
+    }
+    Ok(())
+}
+
+fn end_reached() -> Error {
+    Error::new(ErrorKind::UnexpectedEof, "Reached stream end.")
+}
+
+fn exceeds_end(e: Error) -> Error {
+    match e.kind() {
+        ErrorKind::UnexpectedEof => invalid("Frame exceeds stream end."),
+        _ => e
+    }
+}
+
+fn invalid(msg: &'static str) -> Error {
+    Error::new(ErrorKind::InvalidData, msg)
+}
@@ -0,0 +1,137 @@
+use std::io::{Error, ErrorKind, Read};
+
+use crate::util::crc32c::masked;
+
+/// Framing of a record based file format.
+pub trait Codec {
+    /// Smallest number of bytes a record occupies besides its payload.
+    const OVERHEAD: u64;
+
+    /// Appends the encoded record of data to buf.
+    fn encode(data: &[u8], buf: &mut Vec<u8>);
+
+    /// Reads the header of the next record and returns the header length and the payload length.
+    ///
+    /// If the reader is exhausted before the first byte, None is returned.
+    fn header(reader: &mut impl Read) -> Result<Option<(u64, u64)>, Error>;
+
+    /// Number of bytes following the payload of a record.
+    fn trailer_len() -> u64;
+
+    /// Appends the trailer of a record with payload data to buf.
+    fn trailer(data: &[u8], buf: &mut Vec<u8>);
+
+    /// Verifies the payload of a record against its trailer.
+    fn verify(data: &[u8], trailer: &[u8]) -> Result<(), Error>;
+}
+
+/// TFRecord framing, `[u64 len][masked crc32c of len][data][masked crc32c of data]` in little endian.
+pub struct TfRecord;
+
+impl Codec for TfRecord {
+    const OVERHEAD: u64 = 16;
+
+    fn encode(data: &[u8], buf: &mut Vec<u8>) {
+        let len = u64::to_le_bytes(data.len() as u64);
+        buf.extend_from_slice(&len);
+        buf.extend_from_slice(&u32::to_le_bytes(masked(&len)));
+        buf.extend_from_slice(data);
+        Self::trailer(data, buf);
+    }
+
+    fn header(reader: &mut impl Read) -> Result<Option<(u64, u64)>, Error> {
+        let mut header = [0; 12];
+        if !read_first(reader, &mut header)? {
+            return Ok(None);
+        }
+
+        let len: [u8; 8] = header[..8].try_into().unwrap();
+        if u32::from_le_bytes(header[8..].try_into().unwrap()) != masked(&len) {
+            return Err(Error::new(ErrorKind::InvalidData, "Record length checksum mismatch."));
+        }
+
+        Ok(Some((12, u64::from_le_bytes(len))))
+    }
+
+    fn trailer_len() -> u64 {
+        4
+    }
+
+    fn trailer(data: &[u8], buf: &mut Vec<u8>) {
+        buf.extend_from_slice(&u32::to_le_bytes(masked(data)));
+    }
+
+    fn verify(data: &[u8], trailer: &[u8]) -> Result<(), Error> {
+        if u32::from_le_bytes(trailer.try_into().unwrap()) != masked(data) {
+            return Err(Error::new(ErrorKind::InvalidData, "Record data checksum mismatch."));
+        }
+        Ok(())
+    }
+}
+
+/// Protobuf style framing, a varint length followed by the data.
+pub struct Varint;
+
+impl Codec for Varint {
+    const OVERHEAD: u64 = 1;
+
+    fn encode(data: &[u8], buf: &mut Vec<u8>) {
+        let mut len = data.len() as u64;
+        while len >= 0x80 {
+            buf.push(len as u8 | 0x80);
+            len >>= 7;
+        }
+        buf.push(len as u8);
+        buf.extend_from_slice(data);
+    }
+
+    fn header(reader: &mut impl Read) -> Result<Option<(u64, u64)>, Error> {
+        let mut len = 0u64;
+
+        for i in 0..10 {
+            let mut byte = [0];
+            if !read_first(reader, &mut byte)? {
+                if i == 0 {
+                    return Ok(None);
+                }
+                return Err(truncated());
+            }
+
+            len |= ((byte[0] & 0x7f) as u64) << (7 * i);
+            if byte[0] & 0x80 == 0 {
+                return Ok(Some((i + 1, len)));
+            }
+        }
+
+        Err(Error::new(ErrorKind::InvalidData, "Record length varint is too long."))
+    }
+
+    fn trailer_len() -> u64 {
+        0
+    }
+
+    fn trailer(_: &[u8], _: &mut Vec<u8>) {}
+
+    fn verify(_: &[u8], _: &[u8]) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// Fills buf and returns false if the reader was exhausted before the first byte.
+fn read_first(reader: &mut impl Read, buf: &mut [u8]) -> Result<bool, Error> {
+    let mut n = 0;
+    while n < buf.len() {
+        match reader.read(&mut buf[n..]) {
+            Ok(0) if n == 0 => return Ok(false),
+            Ok(0) => return Err(truncated()),
+            Ok(res) => n += res,
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e)
+        }
+    }
+    Ok(true)
+}
+
+fn truncated() -> Error {
+    Error::new(ErrorKind::InvalidData, "Record is truncated.")
+}
@@ -0,0 +1,12 @@
+
+mod codec;
+mod record_obj_str;
+
+pub use codec::{Codec, TfRecord, Varint};
+pub use record_obj_str::RecordObjStr;
+
+/// Stream of TFRecord files, each record is `[u64 len][masked crc32c of len][data][masked crc32c of data]`.
+pub type TfRecordObjStr = RecordObjStr<TfRecord>;
+
+/// Stream of varint length-delimited records as written by protobuf `writeDelimitedTo`.
+pub type VarintObjStr = RecordObjStr<Varint>;
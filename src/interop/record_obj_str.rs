@@ -0,0 +1,239 @@
+use std::{fs::{File, OpenOptions}, io::{BufReader, Error, ErrorKind, Read as _, Seek as _, SeekFrom, Write as _}, marker::PhantomData, path::Path};
+
+use crate::api::ObjStr;
+
+use super::Codec;
+
+/// Object stream over a foreign record based file format.
+///
+/// The record offsets are indexed when the file is opened, so seeking never touches the file.
+/// Records are verified against their checksums when read, if the format has any.
+pub struct RecordObjStr<C: Codec> {
+    file: File,
+    offsets: Vec<u64>,
+    pos: usize,
+    codec: PhantomData<C>
+}
+
+impl<C: Codec> RecordObjStr<C> {
+    /// Opens or creates the file at path and indexes its records.
+    ///
+    /// If a record is damaged or truncated, error occurs.
+    pub fn new(path: &Path) -> Result<RecordObjStr<C>, Error> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+
+        let offsets = index::<C>(&file)?;
+
+        Ok(
+            RecordObjStr {
+                file,
+                offsets,
+                pos: 0,
+                codec: PhantomData
+            }
+        )
+    }
+
+    /// Returns the number of records in the stream.
+    pub fn count(&self) -> u64 {
+        self.records() as u64
+    }
+
+    fn records(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    fn end(&self) -> u64 {
+        self.offsets[self.records()]
+    }
+
+    fn read_record(&mut self, i: usize) -> Result<Vec<u8>, Error> {
+        self.file.seek(SeekFrom::Start(self.offsets[i]))?;
+        let (_, len) = C::header(&mut self.file)?.ok_or_else(truncated)?;
+
+        let mut data = vec![0; len as usize + C::trailer_len() as usize];
+        self.file.read_exact(&mut data)?;
+
+        let trailer = data.split_off(len as usize);
+        C::verify(&data, &trailer)?;
+
+        Ok(data)
+    }
+
+    /// Returns the number of bytes the record at i occupies besides its payload.
+    fn overhead(&mut self, i: usize) -> Result<u64, Error> {
+        self.file.seek(SeekFrom::Start(self.offsets[i]))?;
+        let (header, _) = C::header(&mut self.file)?.ok_or_else(truncated)?;
+        Ok(header + C::trailer_len())
+    }
+
+    /// Replaces all records from index i on with data.
+    fn rewrite_from(&mut self, i: usize, data: &[&[u8]]) -> Result<(), Error> {
+        let mut buf = Vec::new();
+        self.offsets.truncate(i + 1);
+
+        for d in data {
+            C::encode(d, &mut buf);
+            self.offsets.push(self.offsets[i] + buf.len() as u64);
+        }
+
+        self.file.seek(SeekFrom::Start(self.offsets[i]))?;
+        self.file.write_all(&buf)?;
+        self.file.set_len(self.end())
+    }
+}
+
+impl<C: Codec> ObjStr for RecordObjStr<C> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<(), Error> {
+        let target = match pos {
+            SeekFrom::Start(pos) => pos as i128,
+            SeekFrom::End(pos) => self.records() as i128 - pos.unsigned_abs() as i128,
+            SeekFrom::Current(pos) => self.pos as i128 + pos as i128
+        };
+
+        if target < 0 {
+            self.pos = 0;
+            return Err(Error::new(ErrorKind::UnexpectedEof, "Reached stream start."));
+        }
+        if target > self.records() as i128 {
+            self.pos = self.records();
+            return Err(end_reached());
+        }
+
+        self.pos = target as usize;
+        Ok(())
+    }
+
+    fn read(&mut self) -> Result<Vec<u8>, Error> {
+        if self.pos >= self.records() {
+            return Err(end_reached());
+        }
+
+        let data = self.read_record(self.pos)?;
+        self.pos += 1;
+        Ok(data)
+    }
+
+    /// The overhead of contents is the actual overhead of the records in range,
+    /// contents beyond objs count with the smallest overhead of a record of the format.
+    fn len(&mut self, contents: u8, objs: u8) -> Result<u64, Error> {
+        let end = self.pos + objs as usize;
+        if end > self.records() {
+            return Err(end_reached());
+        }
+
+        if objs == 0 && contents > 0 {
+            return Err(Error::other("To small to fit that many objects"));
+        }
+
+        let mut overhead = 0;
+        for i in 0..contents as usize {
+            overhead += if i < objs as usize {
+                self.overhead(self.pos + i)?
+            } else {
+                C::OVERHEAD
+            };
+        }
+
+        (self.offsets[end] - self.offsets[self.pos]).checked_sub(overhead)
+            .ok_or_else(|| Error::other("To small to fit that many objects"))
+    }
+
+    /// The records following the overwritten ones are rewritten, so the replacement may differ in length.
+    fn overwrite(&mut self, data: &[&[u8]], objs: u8) -> Result<(), Error> {
+        if objs == 0 || data.is_empty() {
+            return Err(Error::other("Nothing to overwrite"));
+        }
+
+        let end = self.pos + objs as usize;
+        if end > self.records() {
+            return Err(end_reached());
+        }
+
+        let mut rest = Vec::with_capacity(self.records() - end);
+        for i in end..self.records() {
+            rest.push(self.read_record(i)?);
+        }
+
+        let mut records = data.to_vec();
+        records.extend(rest.iter().map(Vec::as_slice));
+
+        self.rewrite_from(self.pos, &records)?;
+        self.pos += data.len();
+        Ok(())
+    }
+
+    /// Only the patched bytes and the trailer of the record are written, the following records are not touched.
+    fn patch(&mut self, offset: u64, data: &[u8]) -> Result<(), Error> {
+        if self.pos >= self.records() {
+            return Err(end_reached());
+        }
+
+        let mut payload = self.read_record(self.pos)?;
+
+        let start = usize::try_from(offset).unwrap_or(usize::MAX);
+        match start.checked_add(data.len()) {
+            Some(end) if end <= payload.len() => payload[start..end].copy_from_slice(data),
+            _ => return Err(Error::new(ErrorKind::InvalidInput, "Range exceeds object length."))
+        }
+
+        let mut trailer = Vec::new();
+        C::trailer(&payload, &mut trailer);
+
+        let trailer_start = self.offsets[self.pos + 1] - C::trailer_len();
+        let payload_start = trailer_start - payload.len() as u64;
+
+        self.file.seek(SeekFrom::Start(payload_start + offset))?;
+        self.file.write_all(data)?;
+        self.file.seek(SeekFrom::Start(trailer_start))?;
+        self.file.write_all(&trailer)
+    }
+
+    fn append(&mut self, data: &[u8]) -> Result<(), Error> {
+        let mut buf = Vec::new();
+        C::encode(data, &mut buf);
+
+        self.file.seek(SeekFrom::Start(self.end()))?;
+        self.file.write_all(&buf)?;
+        self.offsets.push(self.end() + buf.len() as u64);
+        Ok(())
+    }
+
+    fn cut(&mut self) -> Result<(), Error> {
+        self.rewrite_from(self.pos, &[])
+    }
+}
+
+/// Scans the record headers of file and returns the offset of every record followed by the file length.
+fn index<C: Codec>(file: &File) -> Result<Vec<u64>, Error> {
+    let len = file.metadata()?.len();
+    let mut reader = BufReader::new(file);
+    let mut offsets: Vec<u64> = vec![0];
+
+    while let Some((header, payload)) = C::header(&mut reader)? {
+        let skip = payload.checked_add(C::trailer_len()).ok_or_else(truncated)?;
+        let next = offsets[offsets.len() - 1]
+            .checked_add(header)
+            .and_then(|next| next.checked_add(skip))
+            .filter(|&next| next <= len)
+            .ok_or_else(truncated)?;
+
+        reader.seek_relative(skip as i64)?;
+        offsets.push(next);
+    }
+
+    Ok(offsets)
+}
+
+fn end_reached() -> Error {
+    Error::new(ErrorKind::UnexpectedEof, "Reached stream end.")
+}
+
+fn truncated() -> Error {
+    Error::new(ErrorKind::InvalidData, "Record is truncated.")
+}
@@ -0,0 +1,55 @@
+
+#[cfg(feature = "std")]
+pub use std::io::{Error, ErrorKind, SeekFrom};
+
+#[cfg(not(feature = "std"))]
+pub use self::no_std::{Error, ErrorKind, SeekFrom};
+
+#[cfg(not(feature = "std"))]
+mod no_std {
+    use core::fmt;
+
+    /// Position of an object in a stream, like `std::io::SeekFrom`.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum SeekFrom {
+        Start(u64),
+        End(i64),
+        Current(i64)
+    }
+
+    /// Subset of `std::io::ErrorKind` used by the crate.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum ErrorKind {
+        InvalidInput,
+        InvalidData,
+        UnexpectedEof,
+        Other
+    }
+
+    /// Error with a kind and a static message, like `std::io::Error`.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct Error {
+        kind: ErrorKind,
+        msg: &'static str
+    }
+
+    impl Error {
+        pub fn new(kind: ErrorKind, msg: &'static str) -> Error {
+            Error { kind, msg }
+        }
+
+        pub fn other(msg: &'static str) -> Error {
+            Error::new(ErrorKind::Other, msg)
+        }
+
+        pub fn kind(&self) -> ErrorKind {
+            self.kind
+        }
+    }
+
+    impl fmt::Display for Error {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(self.msg)
+        }
+    }
+}
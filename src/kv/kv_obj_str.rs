@@ -0,0 +1,306 @@
+use std::{collections::HashMap, fs::{self, File}, io::{Error, ErrorKind, SeekFrom, Write as _}, path::Path};
+
+use crate::{api::ObjStr, file::FileObjStr, frame::stream, util::crc32c::crc32c};
+
+const PUT: u8 = 0;
+const DELETE: u8 = 1;
+const INDEX_MAGIC: &[u8; 4] = b"OKVI";
+
+/// Maps every live key to the byte offset of the frame holding its latest version.
+type Index = HashMap<Vec<u8>, u64>;
+
+/// Key-value store where every object of the underlying stream is a record of a key.
+/// 
+/// A put appends a new version of the key, a delete appends a tombstone.
+/// The index maps every live key to the byte offset of the frame holding its latest version,
+/// so a get reads a single frame.
+pub struct KvObjStr {
+    str: FileObjStr,
+    index: Index,
+    end: u64,
+    tail: u64
+}
+
+impl KvObjStr {
+    /// Builds the index by scanning the whole stream.
+    pub fn new(str: FileObjStr) -> Result<KvObjStr, Error> {
+        let mut kv = KvObjStr {
+            str,
+            index: HashMap::new(),
+            end: 0,
+            tail: 0
+        };
+        kv.replay()?;
+        Ok(kv)
+    }
+
+    /// Loads a persisted index and replays only the records appended after it was saved.
+    /// 
+    /// The index is only used if it is intact and the stream still holds the record it was saved behind,
+    /// at the same byte range and with the same checksum. Otherwise, or if the index file does not exist, the index is rebuilt.
+    pub fn with_index(str: FileObjStr, path: &Path) -> Result<KvObjStr, Error> {
+        let mut kv = KvObjStr {
+            str,
+            index: HashMap::new(),
+            end: 0,
+            tail: 0
+        };
+
+        if path.exists() {
+            if let Some((index, end, tail, crc)) = decode_index(&fs::read(path)?) {
+                if kv.tail_crc(tail, end)? == Some(crc) {
+                    kv.index = index;
+                    kv.end = end;
+                    kv.tail = tail;
+                }
+            }
+        }
+
+        kv.replay()?;
+        Ok(kv)
+    }
+
+    /// Writes the index to the given path, so it can be loaded with `with_index`.
+    /// 
+    /// Besides the entries, the byte range and checksum of the last record are written to recognize the stream by,
+    /// followed by the entry count and a checksum of the index. The index is written to a temporary file
+    /// next to path that is renamed to path, so a crash never leaves a torn index behind.
+    pub fn save_index(&mut self, path: &Path) -> Result<(), Error> {
+        let crc = self.tail_crc(self.tail, self.end)?
+            .ok_or_else(|| Error::new(ErrorKind::InvalidData, "Stream does not match the index."))?;
+
+        let mut buf = Vec::new();
+        buf.extend_from_slice(INDEX_MAGIC);
+        buf.extend_from_slice(&u64::to_be_bytes(self.end));
+        buf.extend_from_slice(&u64::to_be_bytes(self.tail));
+        buf.extend_from_slice(&u32::to_be_bytes(crc));
+        buf.extend_from_slice(&u64::to_be_bytes(self.index.len() as u64));
+        for (key, pos) in &self.index {
+            buf.extend_from_slice(&u32::to_be_bytes(key.len() as u32));
+            buf.extend_from_slice(key);
+            buf.extend_from_slice(&u64::to_be_bytes(*pos));
+        }
+        buf.extend_from_slice(&u32::to_be_bytes(crc32c(&buf)));
+
+        let mut name = path.file_name().unwrap_or_default().to_os_string();
+        name.push(".tmp");
+        let tmp = path.with_file_name(name);
+
+        let res = File::create(&tmp)
+            .and_then(|mut file| {
+                file.write_all(&buf)?;
+                file.sync_all()
+            })
+            .and_then(|_| fs::rename(&tmp, path));
+
+        if res.is_err() && tmp.exists() {
+            let _ = fs::remove_file(&tmp);
+        }
+        res
+    }
+
+    /// Returns the latest value of the key.
+    pub fn get(&mut self, key: &[u8]) -> Result<Option<Vec<u8>>, Error> {
+        let Some(pos) = self.index.get(key).copied() else {
+            return Ok(None);
+        };
+
+        self.str.jump_to(pos);
+        let (kind, record_key, value) = decode_record(self.str.read()?)?;
+
+        if kind != PUT || record_key != key {
+            return Err(Error::new(ErrorKind::InvalidData, "Index does not match the stream."));
+        }
+
+        Ok(Some(value))
+    }
+
+    /// Appends a new version of the key.
+    pub fn put(&mut self, key: &[u8], value: &[u8]) -> Result<(), Error> {
+        let pos = self.append(&encode_record(PUT, key, value)?)?;
+        self.index.insert(key.to_vec(), pos);
+        Ok(())
+    }
+
+    /// Appends a tombstone for the key and returns whether the key was live.
+    pub fn delete(&mut self, key: &[u8]) -> Result<bool, Error> {
+        if !self.index.contains_key(key) {
+            return Ok(false);
+        }
+
+        self.append(&encode_record(DELETE, key, &[])?)?;
+        self.index.remove(key);
+        Ok(true)
+    }
+
+    pub fn contains_key(&self, key: &[u8]) -> bool {
+        self.index.contains_key(key)
+    }
+
+    /// Returns the number of live keys.
+    pub fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.index.is_empty()
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &[u8]> {
+        self.index.keys().map(|key| key.as_slice())
+    }
+
+    /// Rewrites the stream so that only the latest version of every live key remains.
+    /// 
+    /// Records keep their relative order, tombstones and outdated versions are dropped.
+    /// The records are written to a temporary file that is renamed over the stream,
+    /// so a failed compaction leaves the stream untouched.
+    pub fn compact(&mut self) -> Result<(), Error> {
+        let mut live: Vec<(u64, Vec<u8>)> = self.index.iter().map(|(key, pos)| (*pos, key.clone())).collect();
+        live.sort_unstable();
+
+        let mut index = HashMap::with_capacity(live.len());
+        let (mut end, mut tail) = (0, 0);
+
+        let path = self.str.path().to_path_buf();
+        self.str.rewrite_into(&path, |str, out| {
+            for (pos, key) in live {
+                str.jump_to(pos);
+                let record = str.read()?;
+                out.append(&record)?;
+
+                index.insert(key, end);
+                tail = end;
+                end += stream::frame_len(record.len() as u32);
+            }
+            Ok(())
+        })?;
+
+        self.index = index;
+        self.end = end;
+        self.tail = tail;
+        Ok(())
+    }
+
+    pub fn into_inner(self) -> FileObjStr {
+        self.str
+    }
+
+    /// Appends a record and returns the byte offset of its frame.
+    fn append(&mut self, record: &[u8]) -> Result<u64, Error> {
+        self.str.append(record)?;
+
+        let pos = self.end;
+        self.tail = pos;
+        self.end += stream::frame_len(record.len() as u32);
+        Ok(pos)
+    }
+
+    /// Returns the checksum of the record in the byte range tail..end, None if the stream holds no such record.
+    fn tail_crc(&mut self, tail: u64, end: u64) -> Result<Option<u32>, Error> {
+        if end == 0 {
+            return Ok(Some(0));
+        }
+
+        self.str.seek(SeekFrom::End(0))?;
+        if tail >= end || end > self.str.byte_position() {
+            return Ok(None);
+        }
+
+        self.str.jump_to(tail);
+        match self.str.len(0, 1) {
+            Ok(len) if tail + len == end => (),
+            Ok(_) => return Ok(None),
+            Err(e) if matches!(e.kind(), ErrorKind::UnexpectedEof | ErrorKind::InvalidData) => return Ok(None),
+            Err(e) => return Err(e)
+        }
+
+        Ok(Some(crc32c(&self.str.read()?)))
+    }
+
+    fn replay(&mut self) -> Result<(), Error> {
+        self.str.jump_to(self.end);
+
+        loop {
+            let pos = self.str.byte_position();
+            let record = match self.str.read() {
+                Ok(record) => record,
+                Err(e) if e.kind() == ErrorKind::UnexpectedEof => break,
+                Err(e) => return Err(e)
+            };
+
+            let (kind, key, _) = decode_record(record)?;
+            match kind {
+                PUT => self.index.insert(key, pos),
+                _ => self.index.remove(&key)
+            };
+
+            self.tail = pos;
+            self.end = self.str.byte_position();
+        }
+
+        Ok(())
+    }
+}
+
+fn encode_record(kind: u8, key: &[u8], value: &[u8]) -> Result<Vec<u8>, Error> {
+    if key.len() > u32::MAX as usize {
+        return Err(Error::new(ErrorKind::InvalidInput, "Key is too long."));
+    }
+
+    let mut record = Vec::with_capacity(1 + 4 + key.len() + value.len());
+    record.push(kind);
+    record.extend_from_slice(&u32::to_be_bytes(key.len() as u32));
+    record.extend_from_slice(key);
+    record.extend_from_slice(value);
+    Ok(record)
+}
+
+fn decode_record(mut record: Vec<u8>) -> Result<(u8, Vec<u8>, Vec<u8>), Error> {
+    if record.len() < 5 || record[0] > DELETE {
+        return Err(Error::new(ErrorKind::InvalidData, "Malformed record."));
+    }
+
+    let key_len = u32::from_be_bytes(record[1..5].try_into().unwrap()) as usize;
+    if record.len() - 5 < key_len {
+        return Err(Error::new(ErrorKind::InvalidData, "Malformed record."));
+    }
+
+    let value = record.split_off(5 + key_len);
+    let key = record[5..].to_vec();
+    Ok((record[0], key, value))
+}
+
+/// Decodes a saved index, None if it is truncated, its checksum does not match or it holds another number of entries.
+fn decode_index(buf: &[u8]) -> Option<(Index, u64, u64, u32)> {
+    let (mut buf, checksum) = buf.split_last_chunk::<4>()?;
+    if buf.len() < 32 || &buf[..4] != INDEX_MAGIC || crc32c(buf) != u32::from_be_bytes(*checksum) {
+        return None;
+    }
+
+    let end = u64::from_be_bytes(buf[4..12].try_into().unwrap());
+    let tail = u64::from_be_bytes(buf[12..20].try_into().unwrap());
+    let crc = u32::from_be_bytes(buf[20..24].try_into().unwrap());
+    let count = u64::from_be_bytes(buf[24..32].try_into().unwrap());
+    buf = &buf[32..];
+
+    let mut index = HashMap::new();
+    while !buf.is_empty() {
+        if buf.len() < 4 {
+            return None;
+        }
+        let key_len = u32::from_be_bytes(buf[..4].try_into().unwrap()) as usize;
+        if buf.len() - 4 < key_len + 8 {
+            return None;
+        }
+        let key = buf[4..4 + key_len].to_vec();
+        let pos = u64::from_be_bytes(buf[4 + key_len..12 + key_len].try_into().unwrap());
+        if pos > tail {
+            return None;
+        }
+        index.insert(key, pos);
+        buf = &buf[12 + key_len..];
+    }
+
+    (index.len() as u64 == count).then_some((index, end, tail, crc))
+}
@@ -0,0 +1,4 @@
+
+mod kv_obj_str;
+
+pub use kv_obj_str::KvObjStr;
@@ -1,3 +1,21 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
 
 pub mod api;
-pub mod file;
\ No newline at end of file
+#[cfg(feature = "std")]
+pub mod convert;
+#[cfg(feature = "std")]
+pub mod diff;
+#[cfg(feature = "std")]
+pub mod file;
+pub mod frame;
+#[cfg(feature = "std")]
+pub mod interop;
+pub mod io;
+#[cfg(feature = "std")]
+pub mod kv;
+#[cfg(feature = "std")]
+pub mod net;
+#[cfg(feature = "std")]
+mod util;
@@ -0,0 +1,239 @@
+use std::{collections::HashMap, fs, io::{BufReader, BufWriter, Error, ErrorKind, SeekFrom}, net::{TcpListener, TcpStream}, path::PathBuf, sync::{Arc, Mutex}, thread};
+
+use crate::{api::{Meta, ObjStr}, convert::{write_jsonl_record, Encoding}, file::FileObjStr};
+
+use super::http::{Request, Response};
+
+/// Serves the streams in a directory over HTTP.
+/// 
+/// - `GET /streams/{name}/objects/{index}` reads an object, a single byte `Range` is honoured.
+/// - `POST /streams/{name}/objects` appends the request body, its `Content-Type` is kept as metadata.
+/// - `GET /streams/{name}?from=&limit=` lists objects as JSON lines like `export_jsonl`.
+/// - `DELETE /streams/{name}/objects/{index}..` cuts the stream at index.
+/// 
+/// The stream `name` is the file `name` in the directory, POST creates it if it is missing.
+#[derive(Clone)]
+pub struct Gateway {
+    dir: PathBuf,
+    streams: Arc<Mutex<HashMap<String, Arc<Mutex<FileObjStr>>>>>
+}
+
+impl Gateway {
+    pub fn new(dir: impl Into<PathBuf>) -> Gateway {
+        Gateway {
+            dir: dir.into(),
+            streams: Arc::new(Mutex::new(HashMap::new()))
+        }
+    }
+
+    /// Accepts connections on listener forever, each one is handled on its own thread.
+    pub fn serve(&self, listener: TcpListener) -> Result<(), Error> {
+        for stream in listener.incoming() {
+            let stream = stream?;
+            let gateway = self.clone();
+            thread::spawn(move || gateway.handle(stream));
+        }
+        Ok(())
+    }
+
+    /// Handles a single request on the connection and closes it.
+    pub fn handle(&self, stream: TcpStream) -> Result<(), Error> {
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let mut writer = BufWriter::new(stream);
+
+        let response = match Request::read(&mut reader) {
+            Ok(request) => self.respond(&request).unwrap_or_else(|e| error_response(&e)),
+            Err(e) if e.kind() == ErrorKind::Unsupported => Response::text(501, &e.to_string()),
+            Err(e) if e.kind() == ErrorKind::FileTooLarge => Response::text(413, &e.to_string()),
+            Err(e) => Response::text(400, &e.to_string())
+        };
+
+        response.write(&mut writer)
+    }
+
+    fn respond(&self, request: &Request) -> Result<Response, Error> {
+        let segments: Vec<&str> = request.path.trim_start_matches('/').split('/').collect();
+
+        let (name, rest) = match segments.as_slice() {
+            ["streams", name, rest @ ..] if valid_name(name) => (*name, rest),
+            ["streams", _, ..] => return Ok(Response::text(400, "Invalid stream name.")),
+            _ => return Ok(Response::text(404, "Not found."))
+        };
+
+        match (request.method.as_str(), rest) {
+            ("GET", []) => self.list(name, request),
+            ("GET", ["objects", index]) => self.get(name, index, request),
+            ("POST", ["objects"]) => self.append(name, request),
+            ("DELETE", ["objects", index]) => self.cut(name, index),
+            (_, [] | ["objects"] | ["objects", _]) => Ok(Response::text(405, "Method not allowed.")),
+            _ => Ok(Response::text(404, "Not found."))
+        }
+    }
+
+    fn list(&self, name: &str, request: &Request) -> Result<Response, Error> {
+        let from = parse_query(request, "from")?.unwrap_or(0);
+        let limit = parse_query(request, "limit")?.unwrap_or(u64::MAX);
+
+        let str = self.open(name, false)?;
+        let mut str = lock(&str)?;
+
+        let mut body = Vec::new();
+        if from < str.count() {
+            str.seek(SeekFrom::Start(from))?;
+            for index in from..from.saturating_add(limit).min(str.count()) {
+                write_jsonl_record(&mut body, index, &str.read()?, Encoding::Utf8)?;
+            }
+        }
+
+        Ok(
+            Response::new(200)
+                .header("Content-Type", "application/x-ndjson")
+                .body(body)
+        )
+    }
+
+    fn get(&self, name: &str, index: &str, request: &Request) -> Result<Response, Error> {
+        let index: u64 = index.parse().map_err(|_| invalid_input("Invalid object index."))?;
+
+        let str = self.open(name, false)?;
+        let mut str = lock(&str)?;
+
+        if index >= str.count() {
+            return Ok(Response::text(404, "Object not found."));
+        }
+
+        str.seek(SeekFrom::Start(index))?;
+        let meta = str.read_meta()?;
+        str.seek(SeekFrom::Start(index))?;
+
+        let content_type = meta
+            .and_then(|meta| meta.content_type)
+            .unwrap_or_else(|| "application/octet-stream".to_string());
+
+        let Some(range) = request.header("range") else {
+            return Ok(
+                Response::new(200)
+                    .header("Content-Type", content_type)
+                    .header("Accept-Ranges", "bytes")
+                    .body(str.read()?)
+            );
+        };
+
+        // Only the requested range of the payload is read.
+        let len = str.payload_len()?;
+        let Some((start, end)) = parse_range(range, len) else {
+            return Ok(
+                Response::text(416, "Range not satisfiable.")
+                    .header("Content-Range", format!("bytes */{}", len))
+            );
+        };
+
+        Ok(
+            Response::new(206)
+                .header("Content-Type", content_type)
+                .header("Accept-Ranges", "bytes")
+                .header("Content-Range", format!("bytes {}-{}/{}", start, end - 1, len))
+                .body(str.read_range(start, (end - start) as usize)?)
+        )
+    }
+
+    fn append(&self, name: &str, request: &Request) -> Result<Response, Error> {
+        let str = self.open(name, true)?;
+        let mut str = lock(&str)?;
+
+        let index = str.count();
+        match request.header("content-type") {
+            Some(content_type) => str.append_with_meta(
+                &request.body,
+                &Meta { content_type: Some(content_type.to_string()), ..Meta::now() }
+            )?,
+            None => str.append(&request.body)?
+        }
+
+        Ok(
+            Response::text(201, &index.to_string())
+                .header("Location", format!("/streams/{}/objects/{}", name, index))
+        )
+    }
+
+    fn cut(&self, name: &str, index: &str) -> Result<Response, Error> {
+        let index: u64 = index.strip_suffix("..")
+            .and_then(|index| index.parse().ok())
+            .ok_or_else(|| invalid_input("Expected an open object range like 3.."))?;
+
+        let str = self.open(name, false)?;
+        let mut str = lock(&str)?;
+
+        if index > str.count() {
+            return Ok(Response::text(404, "Object not found."));
+        }
+
+        str.seek(SeekFrom::Start(index))?;
+        str.cut()?;
+
+        Ok(Response::new(204))
+    }
+
+    fn open(&self, name: &str, create: bool) -> Result<Arc<Mutex<FileObjStr>>, Error> {
+        let mut streams = lock(&self.streams)?;
+
+        if let Some(str) = streams.get(name) {
+            return Ok(str.clone());
+        }
+
+        let path = self.dir.join(name);
+        if !create && !path.is_file() {
+            return Err(Error::new(ErrorKind::NotFound, "Stream not found."));
+        }
+
+        fs::create_dir_all(&self.dir)?;
+        let str = Arc::new(Mutex::new(FileObjStr::new(&path)?));
+        streams.insert(name.to_string(), str.clone());
+        Ok(str)
+    }
+}
+
+/// Stream names are single path segments, so they can never leave the directory.
+fn valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('.')
+        && name.bytes().all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.'))
+}
+
+/// Resolves a single `bytes=` range against len to a half-open range, None if it is not satisfiable.
+fn parse_range(range: &str, len: u64) -> Option<(u64, u64)> {
+    let (start, end) = range.strip_prefix("bytes=")?.split_once('-')?;
+
+    let (start, end) = if start.is_empty() {
+        let suffix: u64 = end.parse().ok()?;
+        (len.saturating_sub(suffix), len)
+    } else {
+        let start: u64 = start.parse().ok()?;
+        let end = if end.is_empty() { len } else { end.parse::<u64>().ok()?.saturating_add(1).min(len) };
+        (start, end)
+    };
+
+    (start < end).then_some((start, end))
+}
+
+fn parse_query(request: &Request, name: &str) -> Result<Option<u64>, Error> {
+    request.query(name)
+        .map(|value| value.parse().map_err(|_| invalid_input("Invalid query parameter.")))
+        .transpose()
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> Result<std::sync::MutexGuard<'_, T>, Error> {
+    mutex.lock().map_err(|_| Error::other("Stream lock is poisoned."))
+}
+
+fn invalid_input(msg: &'static str) -> Error {
+    Error::new(ErrorKind::InvalidInput, msg)
+}
+
+fn error_response(e: &Error) -> Response {
+    match e.kind() {
+        ErrorKind::NotFound => Response::text(404, &e.to_string()),
+        ErrorKind::InvalidInput => Response::text(400, &e.to_string()),
+        _ => Response::text(500, &e.to_string())
+    }
+}
@@ -0,0 +1,166 @@
+use std::io::{BufRead, Error, ErrorKind, Read as _, Write};
+
+const MAX_HEAD_LINES: usize = 100;
+
+/// Largest request body that is accepted, longer bodies are rejected before they are read.
+pub const MAX_BODY_LEN: u64 = 64 * 1024 * 1024;
+
+/// Parsed HTTP/1.1 request.
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    query: Vec<(String, String)>,
+    headers: Vec<(String, String)>,
+    pub body: Vec<u8>
+}
+
+impl Request {
+    /// Reads a request with a Content-Length delimited body, chunked bodies are not supported.
+    /// 
+    /// If the body is longer than MAX_BODY_LEN, error occurs.
+    pub fn read(reader: &mut impl BufRead) -> Result<Request, Error> {
+        let line = read_line(reader)?;
+        let mut parts = line.split(' ');
+
+        let (Some(method), Some(target), Some(version), None) = (parts.next(), parts.next(), parts.next(), parts.next()) else {
+            return Err(bad_request());
+        };
+        if !version.starts_with("HTTP/1.") {
+            return Err(bad_request());
+        }
+
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+        let query = query.split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+                (key.to_string(), value.to_string())
+            })
+            .collect();
+
+        let mut headers = Vec::new();
+        loop {
+            let line = read_line(reader)?;
+            if line.is_empty() {
+                break;
+            }
+            if headers.len() == MAX_HEAD_LINES {
+                return Err(bad_request());
+            }
+            let (name, value) = line.split_once(':').ok_or_else(bad_request)?;
+            headers.push((name.trim().to_ascii_lowercase(), value.trim().to_string()));
+        }
+
+        let mut request = Request {
+            method: method.to_string(),
+            path: path.to_string(),
+            query,
+            headers,
+            body: Vec::new()
+        };
+
+        if request.header("transfer-encoding").is_some() {
+            return Err(Error::new(ErrorKind::Unsupported, "Chunked bodies are not supported."));
+        }
+
+        if let Some(len) = request.header("content-length") {
+            let len: u64 = len.parse().map_err(|_| bad_request())?;
+            if len > MAX_BODY_LEN {
+                return Err(Error::new(ErrorKind::FileTooLarge, "Request body is too large."));
+            }
+            reader.take(len).read_to_end(&mut request.body)?;
+            if request.body.len() as u64 != len {
+                return Err(bad_request());
+            }
+        }
+
+        Ok(request)
+    }
+
+    /// Returns the value of the header name, which has to be lowercase.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str())
+    }
+
+    pub fn query(&self, name: &str) -> Option<&str> {
+        self.query.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str())
+    }
+}
+
+/// HTTP/1.1 response, the connection is closed after it is written.
+pub struct Response {
+    status: u16,
+    headers: Vec<(&'static str, String)>,
+    body: Vec<u8>
+}
+
+impl Response {
+    pub fn new(status: u16) -> Response {
+        Response {
+            status,
+            headers: Vec::new(),
+            body: Vec::new()
+        }
+    }
+
+    /// Response with a plain text body.
+    pub fn text(status: u16, text: &str) -> Response {
+        Response::new(status)
+            .header("Content-Type", "text/plain; charset=utf-8")
+            .body(format!("{}\n", text).into_bytes())
+    }
+
+    pub fn header(mut self, name: &'static str, value: impl ToString) -> Response {
+        self.headers.push((name, value.to_string()));
+        self
+    }
+
+    pub fn body(mut self, body: Vec<u8>) -> Response {
+        self.body = body;
+        self
+    }
+
+    pub fn write(&self, writer: &mut impl Write) -> Result<(), Error> {
+        write!(writer, "HTTP/1.1 {} {}\r\n", self.status, reason(self.status))?;
+        for (name, value) in &self.headers {
+            write!(writer, "{}: {}\r\n", name, value)?;
+        }
+        write!(writer, "Content-Length: {}\r\nConnection: close\r\n\r\n", self.body.len())?;
+        writer.write_all(&self.body)?;
+        writer.flush()
+    }
+}
+
+fn read_line(reader: &mut impl BufRead) -> Result<String, Error> {
+    let mut line = Vec::new();
+    reader.take(8 * 1024).read_until(b'\n', &mut line)?;
+
+    if line.pop() != Some(b'\n') {
+        return Err(bad_request());
+    }
+    if line.last() == Some(&b'\r') {
+        line.pop();
+    }
+
+    String::from_utf8(line).map_err(|_| bad_request())
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        204 => "No Content",
+        206 => "Partial Content",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        413 => "Content Too Large",
+        416 => "Range Not Satisfiable",
+        501 => "Not Implemented",
+        _ => "Internal Server Error"
+    }
+}
+
+fn bad_request() -> Error {
+    Error::new(ErrorKind::InvalidData, "Malformed request.")
+}
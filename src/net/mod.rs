@@ -0,0 +1,13 @@
+
+#[cfg(feature = "http")]
+mod gateway;
+#[cfg(feature = "http")]
+mod http;
+mod protocol;
+mod remote_obj_str;
+mod server;
+
+#[cfg(feature = "http")]
+pub use gateway::Gateway;
+pub use remote_obj_str::RemoteObjStr;
+pub use server::Server;
@@ -0,0 +1,166 @@
+use std::io::{Error, ErrorKind, Read, SeekFrom, Write};
+
+// Every message is `[u8 tag][u32 body len][body]`, requests are tagged with an op and responses with a status.
+
+pub const SEEK: u8 = 1;
+pub const READ: u8 = 2;
+pub const LEN: u8 = 3;
+pub const OVERWRITE: u8 = 4;
+pub const APPEND: u8 = 5;
+pub const CUT: u8 = 6;
+pub const COUNT: u8 = 7;
+
+/// Largest body a message may have, longer messages are rejected before their body is read.
+pub const MAX_MESSAGE_LEN: u32 = 64 * 1024 * 1024;
+
+pub const OK: u8 = 0;
+pub const ERR: u8 = 1;
+
+const KINDS: [ErrorKind; 6] = [
+    ErrorKind::Other,
+    ErrorKind::NotFound,
+    ErrorKind::InvalidInput,
+    ErrorKind::InvalidData,
+    ErrorKind::UnexpectedEof,
+    ErrorKind::Unsupported
+];
+
+pub fn write_message(stream: &mut impl Write, tag: u8, body: &[u8]) -> Result<(), Error> {
+    let len = u32::try_from(body.len())
+        .ok()
+        .filter(|&len| len <= MAX_MESSAGE_LEN)
+        .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "Message is too long."))?;
+
+    let mut buf = Vec::with_capacity(5 + body.len());
+    buf.push(tag);
+    buf.extend_from_slice(&u32::to_be_bytes(len));
+    buf.extend_from_slice(body);
+
+    stream.write_all(&buf)?;
+    stream.flush()
+}
+
+/// Reads the next message, None if the peer closed the connection between messages.
+/// 
+/// If the body is longer than MAX_MESSAGE_LEN, error occurs.
+pub fn read_message(stream: &mut impl Read) -> Result<Option<(u8, Vec<u8>)>, Error> {
+    let mut header = [0; 5];
+
+    match stream.read_exact(&mut header[..1]) {
+        Ok(()) => {},
+        Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e)
+    }
+    stream.read_exact(&mut header[1..])?;
+
+    let len = u32::from_be_bytes(header[1..].try_into().unwrap());
+    if len > MAX_MESSAGE_LEN {
+        return Err(Error::new(ErrorKind::InvalidData, "Message is too long."));
+    }
+
+    let mut body = vec![0; len as usize];
+    stream.read_exact(&mut body)?;
+
+    Ok(Some((header[0], body)))
+}
+
+pub fn encode_error(e: &Error) -> Vec<u8> {
+    let kind = KINDS.iter().position(|&kind| kind == e.kind()).unwrap_or(0) as u8;
+    let mut body = vec![kind];
+    body.extend_from_slice(e.to_string().as_bytes());
+    body
+}
+
+pub fn decode_error(body: &[u8]) -> Error {
+    let kind = body.first().and_then(|&kind| KINDS.get(kind as usize)).copied().unwrap_or(ErrorKind::Other);
+    Error::new(kind, String::from_utf8_lossy(body.get(1..).unwrap_or_default()).into_owned())
+}
+
+pub fn encode_seek(pos: SeekFrom) -> Vec<u8> {
+    let (whence, n) = match pos {
+        SeekFrom::Start(n) => (0, n.to_be_bytes()),
+        SeekFrom::End(n) => (1, n.to_be_bytes()),
+        SeekFrom::Current(n) => (2, n.to_be_bytes())
+    };
+    let mut body = vec![whence];
+    body.extend_from_slice(&n);
+    body
+}
+
+pub fn decode_seek(body: &[u8]) -> Result<SeekFrom, Error> {
+    let mut body = Body(body);
+    let whence = body.u8()?;
+    let n = body.u64()?;
+    body.finish()?;
+
+    match whence {
+        0 => Ok(SeekFrom::Start(n)),
+        1 => Ok(SeekFrom::End(n as i64)),
+        2 => Ok(SeekFrom::Current(n as i64)),
+        _ => Err(invalid())
+    }
+}
+
+pub fn encode_overwrite(data: &[&[u8]], objs: u8) -> Result<Vec<u8>, Error> {
+    let mut body = vec![objs];
+    body.extend_from_slice(&u32::to_be_bytes(data.len() as u32));
+    for d in data {
+        let len = u32::try_from(d.len())
+            .map_err(|_| Error::new(ErrorKind::InvalidInput, "Data length is too long."))?;
+        body.extend_from_slice(&u32::to_be_bytes(len));
+        body.extend_from_slice(d);
+    }
+    Ok(body)
+}
+
+pub fn decode_overwrite(body: &[u8]) -> Result<(Vec<&[u8]>, u8), Error> {
+    let mut body = Body(body);
+    let objs = body.u8()?;
+    let n = body.u32()?;
+
+    let mut data = Vec::new();
+    for _ in 0..n {
+        let len = body.u32()? as usize;
+        data.push(body.take(len)?);
+    }
+    body.finish()?;
+
+    Ok((data, objs))
+}
+
+/// Cursor over a message body.
+pub struct Body<'a>(pub &'a [u8]);
+
+impl<'a> Body<'a> {
+    pub fn take(&mut self, n: usize) -> Result<&'a [u8], Error> {
+        if self.0.len() < n {
+            return Err(invalid());
+        }
+        let (head, tail) = self.0.split_at(n);
+        self.0 = tail;
+        Ok(head)
+    }
+
+    pub fn u8(&mut self) -> Result<u8, Error> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub fn u32(&mut self) -> Result<u32, Error> {
+        Ok(u32::from_be_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    pub fn u64(&mut self) -> Result<u64, Error> {
+        Ok(u64::from_be_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    pub fn finish(&self) -> Result<(), Error> {
+        if !self.0.is_empty() {
+            return Err(invalid());
+        }
+        Ok(())
+    }
+}
+
+fn invalid() -> Error {
+    Error::new(ErrorKind::InvalidData, "Malformed message.")
+}
@@ -0,0 +1,77 @@
+use std::{io::{BufReader, Error, ErrorKind, SeekFrom}, net::{TcpStream, ToSocketAddrs}};
+
+use crate::api::ObjStr;
+
+use super::protocol::{decode_error, encode_overwrite, encode_seek, read_message, write_message, APPEND, COUNT, CUT, ERR, LEN, OK, OVERWRITE, READ, SEEK};
+
+/// Stream served by an `objstr-server` over TCP.
+/// 
+/// The cursor is kept by the server per connection, appending does not move it.
+pub struct RemoteObjStr {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream
+}
+
+impl RemoteObjStr {
+    pub fn connect(addr: impl ToSocketAddrs) -> Result<RemoteObjStr, Error> {
+        let stream = TcpStream::connect(addr)?;
+        stream.set_nodelay(true)?;
+
+        Ok(
+            RemoteObjStr {
+                reader: BufReader::new(stream.try_clone()?),
+                writer: stream
+            }
+        )
+    }
+
+    /// Returns the number of objects in the stream.
+    pub fn count(&mut self) -> Result<u64, Error> {
+        let body = self.request(COUNT, &[])?;
+        decode_u64(&body)
+    }
+
+    fn request(&mut self, op: u8, body: &[u8]) -> Result<Vec<u8>, Error> {
+        write_message(&mut self.writer, op, body)?;
+
+        match read_message(&mut self.reader)? {
+            Some((OK, body)) => Ok(body),
+            Some((ERR, body)) => Err(decode_error(&body)),
+            Some(_) => Err(Error::new(ErrorKind::InvalidData, "Malformed message.")),
+            None => Err(Error::new(ErrorKind::ConnectionAborted, "Server closed the connection."))
+        }
+    }
+}
+
+impl ObjStr for RemoteObjStr {
+    fn seek(&mut self, pos: SeekFrom) -> Result<(), Error> {
+        self.request(SEEK, &encode_seek(pos)).map(|_| ())
+    }
+
+    fn read(&mut self) -> Result<Vec<u8>, Error> {
+        self.request(READ, &[])
+    }
+
+    fn len(&mut self, contents: u8, objs: u8) -> Result<u64, Error> {
+        let body = self.request(LEN, &[contents, objs])?;
+        decode_u64(&body)
+    }
+
+    fn overwrite(&mut self, data: &[&[u8]], objs: u8) -> Result<(), Error> {
+        self.request(OVERWRITE, &encode_overwrite(data, objs)?).map(|_| ())
+    }
+
+    fn append(&mut self, data: &[u8]) -> Result<(), Error> {
+        self.request(APPEND, data).map(|_| ())
+    }
+
+    fn cut(&mut self) -> Result<(), Error> {
+        self.request(CUT, &[]).map(|_| ())
+    }
+}
+
+fn decode_u64(body: &[u8]) -> Result<u64, Error> {
+    body.try_into()
+        .map(u64::from_be_bytes)
+        .map_err(|_| Error::new(ErrorKind::InvalidData, "Malformed message."))
+}
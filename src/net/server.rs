@@ -0,0 +1,150 @@
+use std::{io::{BufReader, BufWriter, Error, ErrorKind, SeekFrom}, net::{TcpListener, TcpStream}, sync::{Arc, Mutex}, thread};
+
+use crate::{api::ObjStr, file::FileObjStr};
+
+use super::protocol::{decode_overwrite, decode_seek, encode_error, read_message, write_message, Body, APPEND, COUNT, CUT, ERR, LEN, OK, OVERWRITE, READ, SEEK};
+
+/// Serves one stream to any number of `RemoteObjStr` clients.
+/// 
+/// Every connection has its own cursor, kept as an object index and the byte offset of its frame,
+/// so sequential requests resume at the offset. Requests of all connections are applied one at a time.
+#[derive(Clone)]
+pub struct Server {
+    str: Arc<Mutex<FileObjStr>>
+}
+
+impl Server {
+    pub fn new(str: FileObjStr) -> Server {
+        Server {
+            str: Arc::new(Mutex::new(str))
+        }
+    }
+
+    /// Accepts connections on listener forever, each one is handled on its own thread.
+    pub fn serve(&self, listener: TcpListener) -> Result<(), Error> {
+        for stream in listener.incoming() {
+            let stream = stream?;
+            let server = self.clone();
+            thread::spawn(move || server.handle(stream));
+        }
+        Ok(())
+    }
+
+    /// Handles the requests of a single connection until it is closed.
+    pub fn handle(&self, stream: TcpStream) -> Result<(), Error> {
+        stream.set_nodelay(true)?;
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let mut writer = BufWriter::new(stream);
+        let mut cursor = Cursor::default();
+
+        while let Some((op, body)) = read_message(&mut reader)? {
+            let res = {
+                let mut str = self.str.lock()
+                    .map_err(|_| Error::other("Stream lock is poisoned."))?;
+                apply(&mut str, &mut cursor, op, &body)
+            };
+
+            match res {
+                Ok(body) => write_message(&mut writer, OK, &body)?,
+                Err(e) => write_message(&mut writer, ERR, &encode_error(&e))?
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Cursor of a connection.
+#[derive(Default)]
+struct Cursor {
+    index: u64,
+    /// Byte offset of the frame at index and the stream generation it was taken in.
+    offset: Option<(u64, u64)>
+}
+
+impl Cursor {
+    /// Moves the stream cursor to the cursor of the connection.
+    /// 
+    /// The byte offset is used as long as the stream was not modified in place since it was taken,
+    /// otherwise the stream is seeked to the object index.
+    fn restore(&mut self, str: &mut FileObjStr) -> Result<(), Error> {
+        if let Some((offset, generation)) = self.offset {
+            if generation == str.generation() {
+                str.jump_to(offset);
+                return Ok(());
+            }
+        }
+
+        str.seek(SeekFrom::Start(self.index)).or_else(|_| {
+            self.index = str.count();
+            str.seek(SeekFrom::End(0))
+        })
+    }
+
+    /// Takes the byte offset of the stream cursor, which has to be at index.
+    fn save(&mut self, str: &mut FileObjStr) {
+        self.offset = Some((str.byte_position(), str.generation()));
+    }
+}
+
+/// Applies a request at the cursor of a connection and moves the cursor like a local stream would.
+fn apply(str: &mut FileObjStr, cursor: &mut Cursor, op: u8, body: &[u8]) -> Result<Vec<u8>, Error> {
+    if op == COUNT {
+        return Ok(str.count().to_be_bytes().to_vec());
+    }
+
+    cursor.restore(str)?;
+    let offset = str.byte_position();
+
+    let res = apply_at(str, cursor, op, body);
+    match res {
+        // Appending and cutting leave the stream cursor past the stream end, the cursor of the connection stays.
+        Ok(_) if op == APPEND || op == CUT => cursor.offset = Some((offset, str.generation())),
+        Ok(_) => cursor.save(str),
+        Err(_) => cursor.offset = None
+    }
+    res
+}
+
+fn apply_at(str: &mut FileObjStr, cursor: &mut Cursor, op: u8, body: &[u8]) -> Result<Vec<u8>, Error> {
+    match op {
+        SEEK => {
+            let pos = decode_seek(body)?;
+            let res = str.seek(pos);
+            let count = str.count();
+            let target = match pos {
+                SeekFrom::Start(n) => n as i128,
+                SeekFrom::End(n) => count as i128 - n.unsigned_abs() as i128,
+                SeekFrom::Current(n) => cursor.index as i128 + n as i128
+            };
+            cursor.index = target.clamp(0, count as i128) as u64;
+            res.map(|_| Vec::new())
+        },
+        READ => {
+            let data = str.read()?;
+            cursor.index += 1;
+            Ok(data)
+        },
+        LEN => {
+            let mut body = Body(body);
+            let (contents, objs) = (body.u8()?, body.u8()?);
+            body.finish()?;
+            Ok(str.len(contents, objs)?.to_be_bytes().to_vec())
+        },
+        OVERWRITE => {
+            let (data, objs) = decode_overwrite(body)?;
+            str.overwrite(&data, objs)?;
+            cursor.index += data.len() as u64;
+            Ok(Vec::new())
+        },
+        APPEND => {
+            str.append(body)?;
+            Ok(Vec::new())
+        },
+        CUT => {
+            str.cut()?;
+            Ok(Vec::new())
+        },
+        _ => Err(Error::new(ErrorKind::Unsupported, "Unknown operation."))
+    }
+}
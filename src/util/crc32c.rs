@@ -0,0 +1,30 @@
+const POLY: u32 = 0x82f6_3b78;
+const MASK_DELTA: u32 = 0xa282_ead8;
+
+const TABLE: [u32; 256] = table();
+
+const fn table() -> [u32; 256] {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 { crc >> 1 ^ POLY } else { crc >> 1 };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
+
+/// Castagnoli CRC-32 of data.
+pub fn crc32c(data: &[u8]) -> u32 {
+    !data.iter().fold(!0, |crc, &b| TABLE[((crc ^ b as u32) & 0xff) as usize] ^ crc >> 8)
+}
+
+/// CRC-32C masked the way TFRecord stores it.
+pub fn masked(data: &[u8]) -> u32 {
+    crc32c(data).rotate_right(15).wrapping_add(MASK_DELTA)
+}
@@ -0,0 +1,215 @@
+use std::{fs, io::{Cursor, ErrorKind, SeekFrom}, path::{Path, PathBuf}};
+
+use objstr::{api::{Meta, ObjStr as _}, convert::{self, Encoding}, file::FileObjStr};
+
+fn setup(name: &str) -> FileObjStr {
+    let name = format!(".test/convert/convert_test/{}.bin", name);
+    let path = Path::new(
+        name.as_str()
+    );
+    for path in [path.to_path_buf(), path.with_extension("imported")] {
+        if path.exists() {
+            std::fs::remove_file(&path).unwrap();
+        }
+    }
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    let mut str = FileObjStr::new(path).unwrap();
+    str.append(b"Hello").unwrap();
+    str.append(b"").unwrap();
+    str.append(b"comma, \"quote\"\nnew line").unwrap();
+    str.append(&[0xff, 0x00, 0x80, 0x7f]).unwrap();
+    str
+}
+
+fn imported(name: &str) -> FileObjStr {
+    FileObjStr::new(
+        Path::new(format!(".test/convert/convert_test/{}.imported", name).as_str())
+    ).unwrap()
+}
+
+fn dir(name: &str) -> PathBuf {
+    let dir = PathBuf::from(format!(".test/convert/convert_test/{}.dir", name));
+    if dir.exists() {
+        fs::remove_dir_all(&dir).unwrap();
+    }
+    dir
+}
+
+fn teardown(name: &str) -> (Vec<u8>, Vec<u8>) {
+    let path = format!(".test/convert/convert_test/{}.bin", name);
+    let bytes = std::fs::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    let imported = Path::new(&path).with_extension("imported");
+    let imported_bytes = std::fs::read(&imported).unwrap();
+    std::fs::remove_file(&imported).unwrap();
+    (bytes, imported_bytes)
+}
+
+#[test]
+fn jsonl_round_trip() {
+    let mut str = setup("jsonl_round_trip");
+    let mut out = Vec::new();
+    assert_eq!(convert::export_jsonl(&mut str, &mut out, Encoding::Utf8).unwrap(), 4);
+
+    let text = String::from_utf8(out.clone()).unwrap();
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines.len(), 4);
+    assert_eq!(lines[0], "{\"index\":0,\"length\":5,\"encoding\":\"utf8\",\"payload\":\"Hello\"}");
+    assert_eq!(lines[2], "{\"index\":2,\"length\":23,\"encoding\":\"utf8\",\"payload\":\"comma, \\\"quote\\\"\\nnew line\"}");
+    assert_eq!(lines[3], "{\"index\":3,\"length\":4,\"encoding\":\"base64\",\"payload\":\"/wCAfw==\"}");
+
+    let mut dest = imported("jsonl_round_trip");
+    assert_eq!(convert::import_jsonl(&mut dest, Cursor::new(out)).unwrap(), 4);
+    drop(dest);
+
+    let (bytes, imported) = teardown("jsonl_round_trip");
+    assert_eq!(bytes, imported);
+}
+
+#[test]
+fn jsonl_base64_round_trip() {
+    let mut str = setup("jsonl_base64_round_trip");
+    let mut out = Vec::new();
+    convert::export_jsonl(&mut str, &mut out, Encoding::Base64).unwrap();
+
+    let text = String::from_utf8(out.clone()).unwrap();
+    assert!(text.lines().all(|line| line.contains("\"encoding\":\"base64\"")));
+    assert!(text.starts_with("{\"index\":0,\"length\":5,\"encoding\":\"base64\",\"payload\":\"SGVsbG8=\"}"));
+
+    let mut dest = imported("jsonl_base64_round_trip");
+    convert::import_jsonl(&mut dest, Cursor::new(out)).unwrap();
+    drop(dest);
+
+    let (bytes, imported) = teardown("jsonl_base64_round_trip");
+    assert_eq!(bytes, imported);
+}
+
+#[test]
+fn jsonl_rejects_length_mismatch() {
+    setup("jsonl_rejects_length_mismatch");
+    let input = "{\"index\":0,\"length\":4,\"encoding\":\"utf8\",\"payload\":\"Hello\"}\n";
+
+    let mut dest = imported("jsonl_rejects_length_mismatch");
+    assert!(convert::import_jsonl(&mut dest, Cursor::new(input)).is_err());
+    drop(dest);
+
+    let (_, imported) = teardown("jsonl_rejects_length_mismatch");
+    assert_eq!(imported, vec![0]);
+}
+
+#[test]
+fn csv_round_trip() {
+    let mut str = setup("csv_round_trip");
+    let mut out = Vec::new();
+    assert_eq!(convert::export_csv(&mut str, &mut out, Encoding::Utf8).unwrap(), 4);
+
+    let text = String::from_utf8(out.clone()).unwrap();
+    assert!(text.starts_with("index,length,encoding,payload\n0,5,utf8,\"Hello\"\n1,0,utf8,\"\"\n"));
+    assert!(text.contains("2,23,utf8,\"comma, \"\"quote\"\"\nnew line\"\n"));
+
+    let mut dest = imported("csv_round_trip");
+    assert_eq!(convert::import_csv(&mut dest, Cursor::new(out)).unwrap(), 4);
+    drop(dest);
+
+    let (bytes, imported) = teardown("csv_round_trip");
+    assert_eq!(bytes, imported);
+}
+
+#[test]
+fn dir_round_trip() {
+    let mut str = setup("dir_round_trip");
+    let dir = dir("dir_round_trip");
+    assert_eq!(convert::export_dir(&mut str, &dir).unwrap(), 4);
+
+    assert_eq!(fs::read(dir.join("0.bin")).unwrap(), b"Hello");
+    assert_eq!(fs::read(dir.join("3.bin")).unwrap(), vec![0xff, 0x00, 0x80, 0x7f]);
+
+    let mut dest = imported("dir_round_trip");
+    assert_eq!(convert::import_dir(&mut dest, &dir).unwrap(), 4);
+    drop(dest);
+
+    fs::remove_dir_all(&dir).unwrap();
+    let (bytes, imported) = teardown("dir_round_trip");
+    assert_eq!(bytes, imported);
+}
+
+#[test]
+fn dir_rejects_gap() {
+    let mut str = setup("dir_rejects_gap");
+    let dir = dir("dir_rejects_gap");
+    convert::export_dir(&mut str, &dir).unwrap();
+    fs::remove_file(dir.join("1.bin")).unwrap();
+
+    let mut dest = imported("dir_rejects_gap");
+    assert!(convert::import_dir(&mut dest, &dir).is_err());
+    drop(dest);
+
+    fs::remove_dir_all(&dir).unwrap();
+    teardown("dir_rejects_gap");
+}
+
+#[test]
+fn dir_rejects_non_empty() {
+    let mut str = setup("dir_rejects_non_empty");
+    let dir = dir("dir_rejects_non_empty");
+    convert::export_dir(&mut str, &dir).unwrap();
+
+    str.seek(SeekFrom::Start(1)).unwrap();
+    str.cut().unwrap();
+
+    assert_eq!(convert::export_dir(&mut str, &dir).unwrap_err().kind(), ErrorKind::AlreadyExists);
+
+    let mut dest = imported("dir_rejects_non_empty");
+    assert_eq!(convert::import_dir(&mut dest, &dir).unwrap(), 4);
+    drop(dest);
+
+    fs::remove_dir_all(&dir).unwrap();
+    teardown("dir_rejects_non_empty");
+}
+
+#[test]
+fn raw_round_trip() {
+    let mut str = setup("raw_round_trip");
+    let mut out = Vec::new();
+    assert_eq!(convert::export_raw(&mut str, &mut out).unwrap(), 4);
+
+    assert_eq!(&out[..9], &[0, 0, 0, 5, b'H', b'e', b'l', b'l', b'o']);
+    assert_eq!(&out[9..13], &[0, 0, 0, 0]);
+
+    let mut dest = imported("raw_round_trip");
+    assert_eq!(convert::import_raw(&mut dest, Cursor::new(out.clone())).unwrap(), 4);
+    assert!(convert::import_raw(&mut dest, Cursor::new(&out[..out.len() - 1])).is_err());
+    drop(dest);
+
+    let (bytes, imported) = teardown("raw_round_trip");
+    assert_eq!(&imported[..bytes.len() - 1], &bytes[..bytes.len() - 1]);
+}
+
+#[test]
+fn export_rejects_meta() {
+    let mut str = setup("export_rejects_meta");
+    str.append_with_meta(b"Hello", &Meta::now()).unwrap();
+
+    let mut out = Vec::new();
+    let err = convert::export_jsonl(&mut str, &mut out, Encoding::Utf8).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+
+    let mut dest = imported("export_rejects_meta");
+    assert_eq!(convert::import_jsonl(&mut dest, Cursor::new(out)).unwrap(), 4);
+    drop(dest);
+
+    let _ = teardown("export_rejects_meta");
+}
+
+#[test]
+fn csv_rejects_unterminated_field() {
+    setup("csv_rejects_unterminated_field");
+    let input = "index,length,encoding,payload\n0,5,utf8,\"Hello\n";
+
+    let mut dest = imported("csv_rejects_unterminated_field");
+    assert!(convert::import_csv(&mut dest, Cursor::new(input)).is_err());
+    drop(dest);
+
+    let (_, imported) = teardown("csv_rejects_unterminated_field");
+    assert_eq!(imported, vec![0]);
+}
@@ -0,0 +1 @@
+pub mod convert_test;
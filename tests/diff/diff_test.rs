@@ -0,0 +1,168 @@
+use objstr::{api::{Meta, ObjStr as _}, diff::{self, Edit}, frame::StorageObjStr, io::{ErrorKind, SeekFrom}};
+
+fn setup(objs: &[&[u8]]) -> StorageObjStr<Vec<u8>> {
+    let mut str = StorageObjStr::new(Vec::new()).unwrap();
+    for obj in objs {
+        str.append(obj).unwrap();
+    }
+    str
+}
+
+fn objects(str: &mut StorageObjStr<Vec<u8>>) -> Vec<Vec<u8>> {
+    str.seek(SeekFrom::Start(0)).unwrap();
+    let mut objs = Vec::new();
+    loop {
+        match str.read() {
+            Ok(data) => objs.push(data),
+            Err(e) => {
+                assert_eq!(e.kind(), ErrorKind::UnexpectedEof);
+                return objs;
+            }
+        }
+    }
+}
+
+fn roundtrip(a: &[&[u8]], b: &[&[u8]]) -> Vec<Edit> {
+    let mut str_a = setup(a);
+    let mut str_b = setup(b);
+
+    let edits = diff::diff(&mut str_a, &mut str_b).unwrap();
+    diff::patch(&mut str_a, &edits).unwrap();
+
+    assert_eq!(objects(&mut str_a), b.iter().map(|obj| obj.to_vec()).collect::<Vec<_>>());
+    edits
+}
+
+#[test]
+fn diff_equal() {
+    assert!(roundtrip(&[b"a", b"b", b"c"], &[b"a", b"b", b"c"]).is_empty());
+    assert!(roundtrip(&[], &[]).is_empty());
+}
+
+#[test]
+fn diff_edits() {
+    let edits = roundtrip(&[b"a", b"b", b"c", b"d"], &[b"a", b"x", b"c", b"d", b"e"]);
+    assert_eq!(edits, vec![
+        Edit::Change { index: 1, data: b"x".to_vec() },
+        Edit::Insert { index: 4, data: b"e".to_vec() }
+    ]);
+
+    let edits = roundtrip(&[b"a", b"b", b"c", b"d"], &[b"b", b"d"]);
+    assert_eq!(edits, vec![Edit::Remove { index: 0 }, Edit::Remove { index: 2 }]);
+
+    let edits = roundtrip(&[b"b", b"d"], &[b"a", b"b", b"c", b"d"]);
+    assert_eq!(edits, vec![
+        Edit::Insert { index: 0, data: b"a".to_vec() },
+        Edit::Insert { index: 1, data: b"c".to_vec() }
+    ]);
+}
+
+#[test]
+fn diff_mixed() {
+    roundtrip(&[], &[b"a", b"b"]);
+    roundtrip(&[b"a", b"b"], &[]);
+    roundtrip(&[b"a", b"b", b"c", b"a", b"b", b"b", b"a"], &[b"c", b"b", b"a", b"b", b"a", b"c"]);
+    roundtrip(&[b"x", b"", b"y", b"", b"z"], &[b"", b"", b"y", b"w", b"w", b"z", b""]);
+}
+
+/// Number of objects of a that an edit script keeps.
+fn kept(a: &[&[u8]], edits: &[Edit]) -> usize {
+    a.len() - edits.iter().filter(|edit| !matches!(edit, Edit::Insert { .. })).count()
+}
+
+fn lcs_len(a: &[&[u8]], b: &[&[u8]]) -> usize {
+    let mut row = vec![0; b.len() + 1];
+    for x in a {
+        let mut diag = 0;
+        for (j, y) in b.iter().enumerate() {
+            let up = row[j + 1];
+            row[j + 1] = if x == y { diag + 1 } else { up.max(row[j]) };
+            diag = up;
+        }
+    }
+    row[b.len()]
+}
+
+#[test]
+fn diff_shortest() {
+    let alphabet: [&[u8]; 3] = [b"a", b"b", b"c"];
+    let mut seed = 7u32;
+    let mut next = |bound: u32| {
+        seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+        (seed >> 16) % bound
+    };
+
+    for _ in 0..200 {
+        let a: Vec<&[u8]> = (0..next(20)).map(|_| alphabet[next(3) as usize]).collect();
+        let b: Vec<&[u8]> = (0..next(20)).map(|_| alphabet[next(3) as usize]).collect();
+
+        let edits = roundtrip(&a, &b);
+        assert_eq!(kept(&a, &edits), lcs_len(&a, &b), "{:?} {:?}", a, b);
+    }
+}
+
+#[test]
+fn diff_long() {
+    let a: Vec<Vec<u8>> = (0..2000u32).map(|i| i.to_be_bytes().to_vec()).collect();
+    let b: Vec<Vec<u8>> = (0..2000u32).filter(|i| i % 7 != 0).map(|i| (i * (1 + (i % 11 == 0) as u32)).to_be_bytes().to_vec()).collect();
+    let a: Vec<&[u8]> = a.iter().map(Vec::as_slice).collect();
+    let b: Vec<&[u8]> = b.iter().map(Vec::as_slice).collect();
+
+    let edits = roundtrip(&a, &b);
+    assert_eq!(kept(&a, &edits), lcs_len(&a, &b));
+}
+
+#[test]
+fn patch_invalid() {
+    let mut str = setup(&[b"a", b"b"]);
+
+    let edits = [Edit::Remove { index: 1 }, Edit::Remove { index: 0 }];
+    assert_eq!(diff::patch(&mut str, &edits).unwrap_err().kind(), ErrorKind::InvalidInput);
+
+    let edits = [Edit::Remove { index: 2 }];
+    assert_eq!(diff::patch(&mut str, &edits).unwrap_err().kind(), ErrorKind::InvalidInput);
+
+    let edits = [Edit::Remove { index: 0 }, Edit::Change { index: 0, data: b"c".to_vec() }];
+    assert_eq!(diff::patch(&mut str, &edits).unwrap_err().kind(), ErrorKind::InvalidInput);
+
+    assert_eq!(objects(&mut str), vec![b"a".to_vec(), b"b".to_vec()]);
+
+    diff::patch(&mut str, &[Edit::Insert { index: 2, data: b"c".to_vec() }]).unwrap();
+    assert_eq!(objects(&mut str), vec![b"a".to_vec(), b"b".to_vec(), b"c".to_vec()]);
+}
+
+#[test]
+fn patch_keeps_meta() {
+    let meta = Meta { content_type: Some("text/plain".into()), ..Meta::new() };
+
+    let mut str = setup(&[b"a"]);
+    str.append_with_meta(b"b", &meta).unwrap();
+    str.append_with_meta(b"c", &meta).unwrap();
+
+    diff::patch(&mut str, &[Edit::Remove { index: 0 }, Edit::Change { index: 2, data: b"d".to_vec() }]).unwrap();
+
+    assert_eq!(str.read_with_meta().unwrap(), (b"b".to_vec(), Some(meta)));
+    assert_eq!(str.read_with_meta().unwrap(), (b"d".to_vec(), None));
+    assert_eq!(str.read().unwrap_err().kind(), ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn merge_sorted() {
+    let mut sources = [setup(&[b"1a", b"4a", b"7a"]), setup(&[]), setup(&[b"2b", b"4b", b"9b"]), setup(&[b"3c"])];
+    let mut dest = setup(&[]);
+
+    let count = diff::merge(&mut sources, &mut dest, |a, b| a[0].cmp(&b[0])).unwrap();
+    assert_eq!(count, 7);
+    assert_eq!(objects(&mut dest), vec![
+        b"1a".to_vec(), b"2b".to_vec(), b"3c".to_vec(), b"4a".to_vec(), b"4b".to_vec(), b"7a".to_vec(), b"9b".to_vec()
+    ]);
+}
+
+#[test]
+fn merge_unsorted() {
+    let mut sources = [setup(&[b"1", b"3"]), setup(&[b"4", b"2"])];
+    let mut dest = setup(&[]);
+
+    let err = diff::merge(&mut sources, &mut dest, |a, b| a.cmp(b)).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+}
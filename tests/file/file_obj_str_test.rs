@@ -1,6 +1,6 @@
 use std::{fs, io::SeekFrom, path::Path};
 
-use objstr::{api::ObjStr as _, file::FileObjStr};
+use objstr::{api::{Meta, ObjStr as _}, file::FileObjStr};
 
 fn setup(name: &str) -> FileObjStr {
     let name = format!(".test/file/file_obj_str_test/{}.bin", name);
@@ -16,6 +16,12 @@ fn setup(name: &str) -> FileObjStr {
     FileObjStr::new(path).unwrap()
 }
 
+fn setup_existing(name: &str) -> FileObjStr {
+    FileObjStr::new(
+        Path::new(format!(".test/file/file_obj_str_test/{}.bin", name).as_str())
+    ).unwrap()
+}
+
 fn teardown(name: &str) -> Vec<u8> {
     let bytes = std::fs::read(
         format!(".test/file/file_obj_str_test/{}.bin", name).as_str()
@@ -30,7 +36,7 @@ fn teardown(name: &str) -> Vec<u8> {
 fn test_append() {
     let mut str = setup("test_append");
 
-    str.append(b"Hello, world1".to_vec()).unwrap();
+    str.append(b"Hello, world1").unwrap();
 
     let bytes = teardown("test_append");
 
@@ -41,7 +47,7 @@ fn test_append() {
 fn test_read() {
     let mut str = setup("test_read");
 
-    str.append(b"Hello, world1".to_vec()).unwrap();
+    str.append(b"Hello, world1").unwrap();
 
     str.seek(SeekFrom::Start(0)).unwrap();
 
@@ -54,8 +60,8 @@ fn test_read() {
 fn test_seek_start_forward() {
     let mut str = setup("test_seek_start_forward");
 
-    str.append(b"Hello, world1".to_vec()).unwrap();
-    str.append(b"Hello, world2".to_vec()).unwrap();
+    str.append(b"Hello, world1").unwrap();
+    str.append(b"Hello, world2").unwrap();
 
     str.seek(SeekFrom::Start(1)).unwrap();
 
@@ -68,8 +74,8 @@ fn test_seek_start_forward() {
 fn test_seek_current_forward() {
     let mut str = setup("test_seek_current_forward");
 
-    str.append(b"Hello, world1".to_vec()).unwrap();
-    str.append(b"Hello, world2".to_vec()).unwrap();
+    str.append(b"Hello, world1").unwrap();
+    str.append(b"Hello, world2").unwrap();
 
     str.seek(SeekFrom::Start(0)).unwrap();
     str.seek(SeekFrom::Current(1)).unwrap();
@@ -83,8 +89,8 @@ fn test_seek_current_forward() {
 fn test_seek_current_backward() {
     let mut str = setup("test_seek_current_backward");
 
-    str.append(b"Hello, world1".to_vec()).unwrap();
-    str.append(b"Hello, world2".to_vec()).unwrap();
+    str.append(b"Hello, world1").unwrap();
+    str.append(b"Hello, world2").unwrap();
 
     str.seek(SeekFrom::End(0)).unwrap();
     str.seek(SeekFrom::Current(-1)).unwrap();
@@ -98,8 +104,8 @@ fn test_seek_current_backward() {
 fn test_seek_end_forward() {
     let mut str = setup("test_seek_end_forward");
 
-    str.append(b"Hello, world1".to_vec()).unwrap();
-    str.append(b"Hello, world2".to_vec()).unwrap();
+    str.append(b"Hello, world1").unwrap();
+    str.append(b"Hello, world2").unwrap();
 
     str.seek(SeekFrom::End(1)).unwrap();
 
@@ -112,8 +118,8 @@ fn test_seek_end_forward() {
 fn test_seek_end_backward() {
     let mut str = setup("test_seek_end_backward");
 
-    str.append(b"Hello, world1".to_vec()).unwrap();
-    str.append(b"Hello, world2".to_vec()).unwrap();
+    str.append(b"Hello, world1").unwrap();
+    str.append(b"Hello, world2").unwrap();
 
     str.seek(SeekFrom::End(-1)).unwrap();
 
@@ -127,8 +133,8 @@ fn test_seek_end_backward() {
 fn test_len_0_0() {
     let mut str = setup("test_len_0_0");
 
-    str.append(b"Hello, world1".to_vec()).unwrap();
-    str.append(b"Hello, world2".to_vec()).unwrap();
+    str.append(b"Hello, world1").unwrap();
+    str.append(b"Hello, world2").unwrap();
 
     str.seek(SeekFrom::Start(0)).unwrap();
 
@@ -141,8 +147,8 @@ fn test_len_0_0() {
 fn test_len_0_1() {
     let mut str = setup("test_len_0_1");
 
-    str.append(b"Hello, world1".to_vec()).unwrap();
-    str.append(b"Hello, world2".to_vec()).unwrap();
+    str.append(b"Hello, world1").unwrap();
+    str.append(b"Hello, world2").unwrap();
 
     str.seek(SeekFrom::Start(0)).unwrap();
 
@@ -155,8 +161,8 @@ fn test_len_0_1() {
 fn test_len_1_0() {
     let mut str = setup("test_len_1_0");
 
-    str.append(b"Hello, world1".to_vec()).unwrap();
-    str.append(b"Hello, world2".to_vec()).unwrap();
+    str.append(b"Hello, world1").unwrap();
+    str.append(b"Hello, world2").unwrap();
 
     str.seek(SeekFrom::Start(0)).unwrap();
 
@@ -169,8 +175,8 @@ fn test_len_1_0() {
 fn test_len_1_1() {
     let mut str = setup("test_len_1_1");
 
-    str.append(b"Hello, world1".to_vec()).unwrap();
-    str.append(b"Hello, world2".to_vec()).unwrap();
+    str.append(b"Hello, world1").unwrap();
+    str.append(b"Hello, world2").unwrap();
 
     str.seek(SeekFrom::Start(0)).unwrap();
 
@@ -183,8 +189,8 @@ fn test_len_1_1() {
 fn test_len_1_2() {
     let mut str = setup("test_len_1_2");
 
-    str.append(b"Hello, world1".to_vec()).unwrap();
-    str.append(b"Hello, world2".to_vec()).unwrap();
+    str.append(b"Hello, world1").unwrap();
+    str.append(b"Hello, world2").unwrap();
 
     str.seek(SeekFrom::Start(0)).unwrap();
 
@@ -197,8 +203,8 @@ fn test_len_1_2() {
 fn test_len_2_1() {
     let mut str = setup("test_len_2_1");
 
-    str.append(b"Hello, world1".to_vec()).unwrap();
-    str.append(b"Hello, world2".to_vec()).unwrap();
+    str.append(b"Hello, world1").unwrap();
+    str.append(b"Hello, world2").unwrap();
 
     str.seek(SeekFrom::Start(0)).unwrap();
 
@@ -211,8 +217,8 @@ fn test_len_2_1() {
 fn test_cut_random() {
     let mut str = setup("test_cut_random");
 
-    str.append(b"Hello, world1".to_vec()).unwrap();
-    str.append(b"Hello, world2".to_vec()).unwrap();
+    str.append(b"Hello, world1").unwrap();
+    str.append(b"Hello, world2").unwrap();
 
     str.seek(SeekFrom::Start(1)).unwrap();
 
@@ -227,8 +233,8 @@ fn test_cut_random() {
 fn test_cut_start() {
     let mut str = setup("test_cut_start");
 
-    str.append(b"Hello, world1".to_vec()).unwrap();
-    str.append(b"Hello, world2".to_vec()).unwrap();
+    str.append(b"Hello, world1").unwrap();
+    str.append(b"Hello, world2").unwrap();
 
     str.seek(SeekFrom::Start(0)).unwrap();
 
@@ -243,8 +249,8 @@ fn test_cut_start() {
 fn test_cut_end() {
     let mut str = setup("test_cut_end");
 
-    str.append(b"Hello, world1".to_vec()).unwrap();
-    str.append(b"Hello, world2".to_vec()).unwrap();
+    str.append(b"Hello, world1").unwrap();
+    str.append(b"Hello, world2").unwrap();
 
     str.seek(SeekFrom::End(0)).unwrap();
 
@@ -259,11 +265,11 @@ fn test_cut_end() {
 fn test_overwrite_0_0() {
     let mut str = setup("test_overwrite_0_0");
 
-    str.append(b"Hello, world1".to_vec()).unwrap();
+    str.append(b"Hello, world1").unwrap();
 
     str.seek(SeekFrom::Start(0)).unwrap();
 
-    str.overwrite(vec![], 0).unwrap();
+    str.overwrite(&[], 0).unwrap();
 
     let bytes = teardown("test_overwrite_0_0");
 
@@ -274,11 +280,11 @@ fn test_overwrite_0_0() {
 fn test_overwrite_0_1() {
     let mut str = setup("test_overwrite_0_1");
 
-    str.append(b"Hello, world1".to_vec()).unwrap();
+    str.append(b"Hello, world1").unwrap();
 
     str.seek(SeekFrom::Start(0)).unwrap();
 
-    assert!(str.overwrite(vec![b"Hello, world2".to_vec()], 0).is_err());
+    assert!(str.overwrite(&[b"Hello, world2".as_slice()], 0).is_err());
 
     let _ = teardown("test_overwrite_0_1");
 }
@@ -287,11 +293,11 @@ fn test_overwrite_0_1() {
 fn test_overwrite_1_1() {
     let mut str = setup("test_overwrite_1_1");
 
-    str.append(b"Hello, world1".to_vec()).unwrap();
+    str.append(b"Hello, world1").unwrap();
 
     str.seek(SeekFrom::Start(0)).unwrap();
 
-    str.overwrite(vec![b"Hello, world2".to_vec()], 1).unwrap();
+    str.overwrite(&[b"Hello, world2".as_slice()], 1).unwrap();
 
     let bytes = teardown("test_overwrite_1_1");
 
@@ -302,12 +308,12 @@ fn test_overwrite_1_1() {
 fn test_overwrite_1_2() {
     let mut str = setup("test_overwrite_1_2");
 
-    str.append(b"Hello, world1".to_vec()).unwrap();
-    str.append(b"Hello, world2".to_vec()).unwrap();
+    str.append(b"Hello, world1").unwrap();
+    str.append(b"Hello, world2").unwrap();
 
     str.seek(SeekFrom::Start(0)).unwrap();
 
-    str.overwrite(vec![b"Hello, world2 --  -- Hello, world2".to_vec()], 2).unwrap();
+    str.overwrite(&[b"Hello, world2 --  -- Hello, world2".as_slice()], 2).unwrap();
 
     let bytes = teardown("test_overwrite_1_2");
 
@@ -318,13 +324,13 @@ fn test_overwrite_1_2() {
 fn test_overwrite_2_1() {
     let mut str = setup("test_overwrite_2_1");
 
-    str.append(b"Hello, world1".to_vec()).unwrap();
+    str.append(b"Hello, world1").unwrap();
     
     str.seek(SeekFrom::Start(0)).unwrap();
 
     println!("{:?}", str.len(2, 1).unwrap());
 
-    str.overwrite(vec![b"Hey".to_vec(), b"Ey".to_vec()], 1).unwrap();
+    str.overwrite(&[b"Hey".as_slice(), b"Ey".as_slice()], 1).unwrap();
 
     let bytes = teardown("test_overwrite_2_1");
 
@@ -335,8 +341,8 @@ fn test_overwrite_2_1() {
 fn test_seek_start_forward_error() {
     let mut str = setup("test_seek_start_forward_error");
 
-    str.append(b"Hello, world1".to_vec()).unwrap();
-    str.append(b"Hello, world2".to_vec()).unwrap();
+    str.append(b"Hello, world1").unwrap();
+    str.append(b"Hello, world2").unwrap();
 
     assert!(str.seek(SeekFrom::Start(100)).is_err());
 
@@ -349,8 +355,8 @@ fn test_seek_start_forward_error() {
 fn test_seek_current_forward_error() {
     let mut str = setup("test_seek_current_forward_error");
 
-    str.append(b"Hello, world1".to_vec()).unwrap();
-    str.append(b"Hello, world2".to_vec()).unwrap();
+    str.append(b"Hello, world1").unwrap();
+    str.append(b"Hello, world2").unwrap();
 
     
     assert!(str.seek(SeekFrom::Start(0)).is_ok());
@@ -365,8 +371,8 @@ fn test_seek_current_forward_error() {
 fn test_seek_current_backward_error() {
     let mut str = setup("test_seek_current_backward_error");
 
-    str.append(b"Hello, world1".to_vec()).unwrap();
-    str.append(b"Hello, world2".to_vec()).unwrap();
+    str.append(b"Hello, world1").unwrap();
+    str.append(b"Hello, world2").unwrap();
 
     
     assert!(str.seek(SeekFrom::End(0)).is_ok());
@@ -381,8 +387,8 @@ fn test_seek_current_backward_error() {
 fn test_seek_end_forward_error() {
     let mut str = setup("test_seek_end_forward_error");
 
-    str.append(b"Hello, world1".to_vec()).unwrap();
-    str.append(b"Hello, world2".to_vec()).unwrap();
+    str.append(b"Hello, world1").unwrap();
+    str.append(b"Hello, world2").unwrap();
 
     
     assert!(str.seek(SeekFrom::End(100)).is_err());
@@ -396,8 +402,8 @@ fn test_seek_end_forward_error() {
 fn test_seek_end_backward_error() {
     let mut str = setup("test_seek_end_backward_error");
 
-    str.append(b"Hello, world1".to_vec()).unwrap();
-    str.append(b"Hello, world2".to_vec()).unwrap();
+    str.append(b"Hello, world1").unwrap();
+    str.append(b"Hello, world2").unwrap();
 
     
     assert!(str.seek(SeekFrom::End(-100)).is_err());
@@ -406,3 +412,541 @@ fn test_seek_end_backward_error() {
 
     let _ = teardown("test_seek_end_backward_error");
 }
+
+#[test]
+fn test_trim_front() {
+    let mut str = setup("test_trim_front");
+
+    str.append(b"Hello, world1").unwrap();
+    str.append(b"Hello, world2").unwrap();
+
+    str.seek(SeekFrom::End(-1)).unwrap();
+
+    str.trim_front(1).unwrap();
+
+    assert_eq!(str.read().unwrap(), b"Hello, world2".to_vec());
+
+    let bytes = teardown("test_trim_front");
+
+    assert_eq!(bytes, b"\x00\x00\x00\x0dHello, world2\x00\x00\x00\x0d\x00".to_vec());
+}
+
+#[test]
+fn test_trim_front_error() {
+    let mut str = setup("test_trim_front_error");
+
+    str.append(b"Hello, world1").unwrap();
+
+    assert!(str.trim_front(2).is_err());
+
+    let bytes = teardown("test_trim_front_error");
+
+    assert_eq!(bytes, b"\x00\x00\x00\x0dHello, world1\x00\x00\x00\x0d\x00".to_vec());
+}
+
+#[test]
+fn test_append_with_meta() {
+    let mut str = setup("test_append_with_meta");
+
+    let mut meta = Meta::new();
+    meta.content_type = Some("text/plain".to_string());
+
+    str.append_with_meta(b"Hello, world1", &meta).unwrap();
+
+    let bytes = teardown("test_append_with_meta");
+
+    assert_eq!(bytes, b"\x80\x00\x00\x24OSM\x01\x00\x00\x00\x0f\x01\x00\x0atext/plain\x00\x00Hello, world1\x80\x00\x00\x24\x00".to_vec());
+}
+
+#[test]
+fn test_read_with_meta() {
+    let mut str = setup("test_read_with_meta");
+
+    let mut meta = Meta::now();
+    meta.content_type = Some("text/plain".to_string());
+    meta.attributes.insert("key".to_string(), "value".to_string());
+
+    str.append(b"Hello, world1").unwrap();
+    str.append_with_meta(b"Hello, world2", &meta).unwrap();
+
+    str.seek(SeekFrom::Start(0)).unwrap();
+
+    assert_eq!(str.read_with_meta().unwrap(), (b"Hello, world1".to_vec(), None));
+    assert_eq!(str.read_with_meta().unwrap(), (b"Hello, world2".to_vec(), Some(meta)));
+
+    let _ = teardown("test_read_with_meta");
+}
+
+#[test]
+fn test_read_meta() {
+    let mut str = setup("test_read_meta");
+
+    let mut meta = Meta::new();
+    meta.attributes.insert("type".to_string(), "greeting".to_string());
+
+    str.append(b"Hello, world1").unwrap();
+    str.append_with_meta(b"Hello, world2", &meta).unwrap();
+    str.append(b"Hello, world3").unwrap();
+
+    str.seek(SeekFrom::Start(0)).unwrap();
+
+    assert_eq!(str.read_meta().unwrap(), None);
+    assert_eq!(str.read_meta().unwrap(), Some(meta));
+    assert_eq!(str.read().unwrap(), b"Hello, world3".to_vec());
+
+    let _ = teardown("test_read_meta");
+}
+
+#[test]
+fn test_seek_with_meta() {
+    let mut str = setup("test_seek_with_meta");
+
+    str.append_with_meta(b"Hello, world1", &Meta::now()).unwrap();
+    str.append_with_meta(b"Hello, world2", &Meta::now()).unwrap();
+
+    str.seek(SeekFrom::End(-1)).unwrap();
+
+    assert_eq!(str.read().unwrap(), b"Hello, world2".to_vec());
+
+    str.seek(SeekFrom::Current(-2)).unwrap();
+
+    assert_eq!(str.read().unwrap(), b"Hello, world1".to_vec());
+
+    let _ = teardown("test_seek_with_meta");
+}
+
+#[test]
+fn test_compact() {
+    let mut str = setup("test_compact");
+
+    str.append(b"Hello, world1").unwrap();
+    str.append(b"Hello, world2").unwrap();
+    str.append(b"Hello, world3").unwrap();
+
+    str.compact(|data| if data.ends_with(b"2") { None } else { Some(data) }).unwrap();
+
+    assert_eq!(str.count(), 2);
+
+    str.append(b"Hello, world4").unwrap();
+
+    let bytes = teardown("test_compact");
+
+    assert_eq!(bytes, b"\x00\x00\x00\x0dHello, world1\x00\x00\x00\x0d\x00\x00\x00\x0dHello, world3\x00\x00\x00\x0d\x00\x00\x00\x0dHello, world4\x00\x00\x00\x0d\x00".to_vec());
+}
+
+#[test]
+fn test_compact_into() {
+    let mut str = setup("test_compact_into");
+
+    str.append(b"Hello, world1").unwrap();
+    str.append_with_meta(b"Hello, world2", &Meta::now()).unwrap();
+
+    str.compact_into(Path::new(".test/file/file_obj_str_test/test_compact_into_dest.bin"), |data| Some(data[7..].to_vec())).unwrap();
+
+    let mut dest = setup_existing("test_compact_into_dest");
+
+    assert_eq!(dest.read_with_meta().unwrap().0, b"world1".to_vec());
+    assert!(dest.read_with_meta().unwrap().1.is_some());
+    assert_eq!(str.read().unwrap(), b"Hello, world1".to_vec());
+
+    let _ = teardown("test_compact_into_dest");
+    let _ = teardown("test_compact_into");
+}
+
+#[test]
+fn test_compact_into_alias() {
+    let mut str = setup("test_compact_into_alias");
+
+    str.append(b"Hello, world1").unwrap();
+    str.append(b"Hello, world2").unwrap();
+
+    str.compact_into(Path::new("./.test/file/../file/file_obj_str_test/test_compact_into_alias.bin"), Some).unwrap();
+    str.append(b"Hello, world3").unwrap();
+
+    assert_eq!(setup_existing("test_compact_into_alias").count(), 3);
+    assert!(!Path::new(".test/file/file_obj_str_test/test_compact_into_alias.bin.compact").exists());
+
+    let _ = teardown("test_compact_into_alias");
+}
+
+#[test]
+fn test_append_batch() {
+    let mut str = setup("test_append_batch");
+
+    str.append(b"Hello, world1").unwrap();
+    str.append_batch([b"Hello, world2".as_slice(), b"Hello, world3".as_slice()]).unwrap();
+
+    assert_eq!(str.count(), 3);
+
+    let bytes = teardown("test_append_batch");
+
+    assert_eq!(bytes, b"\x00\x00\x00\x0dHello, world1\x00\x00\x00\x0d\x00\x00\x00\x0dHello, world2\x00\x00\x00\x0d\x00\x00\x00\x0dHello, world3\x00\x00\x00\x0d\x00".to_vec());
+}
+
+#[test]
+fn test_buffered_appender() {
+    let mut str = setup("test_buffered_appender");
+
+    let mut appender = str.buffered_appender(40);
+
+    appender.append(b"Hello, world1").unwrap();
+    appender.append(b"Hello, world2").unwrap();
+    appender.append(b"Hello, world3").unwrap();
+
+    drop(appender);
+
+    str.seek(SeekFrom::End(-1)).unwrap();
+
+    assert_eq!(str.read().unwrap(), b"Hello, world3".to_vec());
+    assert_eq!(str.count(), 3);
+
+    let _ = teardown("test_buffered_appender");
+}
+
+#[test]
+fn test_read_ahead() {
+    let mut str = setup("test_read_ahead");
+
+    str.set_read_ahead(Some(32));
+
+    str.append(b"Hello, world1").unwrap();
+    str.append_with_meta(b"Hello, world2", &Meta::now()).unwrap();
+    str.append(b"Hello, world3").unwrap();
+
+    str.seek(SeekFrom::Start(0)).unwrap();
+
+    assert_eq!(str.read().unwrap(), b"Hello, world1".to_vec());
+    assert_eq!(str.read().unwrap(), b"Hello, world2".to_vec());
+    assert_eq!(str.read().unwrap(), b"Hello, world3".to_vec());
+    assert!(str.read().is_err());
+
+    str.seek(SeekFrom::Start(1)).unwrap();
+
+    assert_eq!(str.read().unwrap(), b"Hello, world2".to_vec());
+
+    let _ = teardown("test_read_ahead");
+}
+
+#[test]
+fn test_read_ahead_invalidate() {
+    let mut str = setup("test_read_ahead_invalidate");
+
+    str.set_read_ahead(Some(1024));
+
+    str.append(b"Hello, world1").unwrap();
+    str.append(b"Hello, world2").unwrap();
+
+    str.seek(SeekFrom::Start(0)).unwrap();
+
+    assert_eq!(str.read().unwrap(), b"Hello, world1".to_vec());
+
+    str.overwrite(&[b"Hello, world3".as_slice()], 1).unwrap();
+    str.append(b"Hello, world4").unwrap();
+    str.seek(SeekFrom::Start(1)).unwrap();
+
+    assert_eq!(str.read().unwrap(), b"Hello, world3".to_vec());
+
+    str.cut().unwrap();
+    str.seek(SeekFrom::Start(0)).unwrap();
+
+    assert_eq!(str.read().unwrap(), b"Hello, world1".to_vec());
+    assert_eq!(str.read().unwrap(), b"Hello, world3".to_vec());
+    assert!(str.read().is_err());
+
+    let _ = teardown("test_read_ahead_invalidate");
+}
+
+#[test]
+fn test_read_into() {
+    let mut str = setup("test_read_into");
+
+    str.append(b"Hello, world1").unwrap();
+    str.append_with_meta(b"Hello, world2", &Meta::now()).unwrap();
+
+    str.seek(SeekFrom::Start(0)).unwrap();
+
+    let mut buf = b"Hello, world0 -- Hello, world0".to_vec();
+
+    str.read_into(&mut buf).unwrap();
+
+    assert_eq!(buf, b"Hello, world1".to_vec());
+
+    str.read_into(&mut buf).unwrap();
+
+    assert_eq!(buf, b"Hello, world2".to_vec());
+    assert!(str.read_into(&mut buf).is_err());
+
+    let _ = teardown("test_read_into");
+}
+
+#[test]
+fn test_read_range() {
+    let mut str = setup("test_read_range");
+
+    str.append(b"Hello, world1").unwrap();
+    str.append_with_meta(b"Hello, world2", &Meta::now()).unwrap();
+
+    str.seek(SeekFrom::Start(0)).unwrap();
+
+    assert_eq!(str.read_range(7, 5).unwrap(), b"world".to_vec());
+    assert_eq!(str.read_range(0, 13).unwrap(), b"Hello, world1".to_vec());
+    assert!(str.read_range(7, 7).is_err());
+    assert_eq!(str.read().unwrap(), b"Hello, world1".to_vec());
+
+    assert_eq!(str.read_range(12, 1).unwrap(), b"2".to_vec());
+    assert_eq!(str.read().unwrap(), b"Hello, world2".to_vec());
+
+    assert!(str.read_range(0, 1).is_err());
+
+    let _ = teardown("test_read_range");
+}
+
+#[test]
+fn test_patch() {
+    let mut str = setup("test_patch");
+
+    str.append(b"Hello, world1").unwrap();
+    str.append_with_meta(b"Hello, world2", &Meta::now()).unwrap();
+
+    str.seek(SeekFrom::Start(0)).unwrap();
+
+    str.patch(7, b"WORLD").unwrap();
+    assert!(str.patch(12, b"12").is_err());
+
+    assert_eq!(str.read().unwrap(), b"Hello, WORLD1".to_vec());
+
+    str.patch(0, b"J").unwrap();
+
+    assert_eq!(str.read_with_meta().unwrap().0, b"Jello, world2".to_vec());
+    assert!(str.patch(0, b"J").is_err());
+
+    str.seek(SeekFrom::Start(0)).unwrap();
+
+    assert_eq!(str.read().unwrap(), b"Hello, WORLD1".to_vec());
+
+    let _ = teardown("test_patch");
+}
+
+#[test]
+fn test_partition_point() {
+    let mut str = setup("test_partition_point");
+
+    for i in 0..100u32 {
+        let mut data = u32::to_be_bytes(i * 2).to_vec();
+        data.resize(4 + (i as usize * 7) % 23, b'x');
+        str.append(&data).unwrap();
+    }
+
+    let idx = str.partition_point(|obj| u32::from_be_bytes(obj[..4].try_into().unwrap()) < 77).unwrap();
+
+    assert_eq!(idx, 39);
+    assert_eq!(&str.read().unwrap()[..4], &u32::to_be_bytes(78));
+
+    assert_eq!(str.partition_point(|_| true).unwrap(), 100);
+    assert_eq!(str.partition_point(|_| false).unwrap(), 0);
+
+    let _ = teardown("test_partition_point");
+}
+
+#[test]
+fn test_partition_point_probes() {
+    let mut str = setup("test_partition_point_probes");
+
+    str.append_batch((0..1000u32).map(|i| {
+        let mut data = u32::to_be_bytes(i).to_vec();
+        data.resize(4 + (i as usize * 13) % 40, b'x');
+        data
+    })).unwrap();
+
+    let bytes = fs::metadata(".test/file/file_obj_str_test/test_partition_point_probes.bin").unwrap().len();
+
+    for target in [0, 1, 500, 777, 999, 1000] {
+        let mut probes = 0;
+        let idx = str.partition_point(|obj| {
+            probes += 1;
+            u32::from_be_bytes(obj[..4].try_into().unwrap()) < target
+        }).unwrap();
+
+        assert_eq!(idx, target as u64);
+        assert!(probes <= bytes.ilog2() + 1, "{} probes", probes);
+
+        match str.read() {
+            Ok(obj) => assert_eq!(&obj[..4], &u32::to_be_bytes(target)),
+            Err(_) => assert_eq!(target, 1000)
+        }
+    }
+
+    let _ = teardown("test_partition_point_probes");
+}
+
+#[test]
+fn test_binary_search_by() {
+    let mut str = setup("test_binary_search_by");
+
+    for i in 0..50u32 {
+        str.append_with_meta(&u32::to_be_bytes(i * 2), &Meta::now()).unwrap();
+    }
+
+    let search = |str: &mut FileObjStr, key: u32| str.binary_search_by(|obj| u32::from_be_bytes(obj.try_into().unwrap()).cmp(&key)).unwrap();
+
+    assert_eq!(search(&mut str, 42), Ok(21));
+    assert_eq!(str.read().unwrap(), u32::to_be_bytes(42).to_vec());
+    assert_eq!(search(&mut str, 43), Err(22));
+    assert_eq!(search(&mut str, 0), Ok(0));
+    assert_eq!(search(&mut str, 1000), Err(50));
+
+    let _ = teardown("test_binary_search_by");
+}
+
+#[test]
+fn test_sync_to_frame() {
+    let mut str = setup("test_sync_to_frame");
+
+    str.append(b"Hello, world1").unwrap();
+    str.append_with_meta(b"Hello, world2", &Meta::now()).unwrap();
+    str.append(b"\x00\x00\x00\x01A\x00\x00\x00\x01").unwrap();
+    str.append(b"Hello, world4").unwrap();
+
+    assert_eq!(str.sync_to_frame(0).unwrap(), Some(0));
+    assert_eq!(str.sync_to_frame(1).unwrap(), Some(21));
+
+    str.read().unwrap();
+    let third = str.byte_position();
+
+    assert_eq!(str.sync_to_frame(third + 1).unwrap(), Some(third + 17));
+    assert_eq!(str.read().unwrap(), b"Hello, world4".to_vec());
+
+    let end = str.byte_position();
+
+    assert_eq!(str.sync_to_frame(end - 3).unwrap(), None);
+    assert!(str.read().is_err());
+
+    let _ = teardown("test_sync_to_frame");
+}
+
+#[test]
+fn test_sync_to_frame_damaged() {
+    let mut str = setup("test_sync_to_frame_damaged");
+
+    str.append(b"Hello, world1").unwrap();
+    str.append(b"Hello, world2").unwrap();
+    str.append(b"Hello, world3").unwrap();
+
+    drop(str);
+
+    let path = ".test/file/file_obj_str_test/test_sync_to_frame_damaged.bin";
+    let mut bytes = fs::read(path).unwrap();
+    bytes[21 + 2] = 0xff;
+    fs::write(path, bytes).unwrap();
+
+    let mut str = setup_existing("test_sync_to_frame_damaged");
+
+    assert_eq!(str.sync_to_frame(21).unwrap(), Some(42));
+    assert_eq!(str.read().unwrap(), b"Hello, world3".to_vec());
+
+    let _ = teardown("test_sync_to_frame_damaged");
+}
+
+#[test]
+fn test_read_length_mismatch() {
+    let mut str = setup("test_read_length_mismatch");
+
+    str.append(b"Hello, world1").unwrap();
+    drop(str);
+
+    let path = ".test/file/file_obj_str_test/test_read_length_mismatch.bin";
+    let mut bytes = std::fs::read(path).unwrap();
+    bytes[20] = 0x0c;
+    std::fs::write(path, bytes).unwrap();
+
+    let mut str = setup_existing("test_read_length_mismatch");
+
+    assert_eq!(str.read().unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+
+    str.set_read_ahead(Some(64));
+    str.seek(SeekFrom::Start(0)).unwrap();
+
+    assert_eq!(str.read().unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+
+    let _ = teardown("test_read_length_mismatch");
+}
+
+#[test]
+fn test_read_ahead_truncated() {
+    let mut str = setup("test_read_ahead_truncated");
+
+    str.append(b"Hello, world1").unwrap();
+    str.append(b"Hello, world2").unwrap();
+    drop(str);
+
+    let path = ".test/file/file_obj_str_test/test_read_ahead_truncated.bin";
+    let mut bytes = std::fs::read(path).unwrap();
+    bytes[21..25].copy_from_slice(&64u32.to_be_bytes());
+    std::fs::write(path, &bytes).unwrap();
+
+    let mut str = setup_existing("test_read_ahead_truncated");
+    str.set_read_ahead(Some(16));
+    str.seek(SeekFrom::Start(0)).unwrap();
+
+    assert_eq!(str.read().unwrap(), b"Hello, world1".to_vec());
+    assert_eq!(str.read().unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+
+    bytes[21..25].copy_from_slice(&0x7fff_0000u32.to_be_bytes());
+    std::fs::write(path, &bytes).unwrap();
+
+    str.seek(SeekFrom::Start(1)).unwrap();
+
+    assert_eq!(str.read().unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+
+    let _ = teardown("test_read_ahead_truncated");
+}
+
+#[test]
+fn test_append_too_long() {
+    let mut str = setup("test_append_too_long");
+
+    let data = vec![0; objstr::frame::LEN_MASK as usize + 1];
+
+    assert_eq!(str.append(&data).unwrap_err().kind(), std::io::ErrorKind::InvalidInput);
+    assert_eq!(str.append_batch([&data]).unwrap_err().kind(), std::io::ErrorKind::InvalidInput);
+    assert_eq!(str.count(), 0);
+
+    let bytes = teardown("test_append_too_long");
+
+    assert_eq!(bytes, b"\x00".to_vec());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_read_legacy_large_frame() {
+    use std::os::unix::fs::FileExt as _;
+
+    let name = "test_read_legacy_large_frame";
+    drop(setup(name));
+
+    let len: u32 = (1 << 31) + 4;
+    let path = format!(".test/file/file_obj_str_test/{}.bin", name);
+    let file = fs::OpenOptions::new().write(true).open(&path).unwrap();
+    file.write_all_at(&len.to_be_bytes(), 0).unwrap();
+    file.write_all_at(b"Hello", 4).unwrap();
+    file.write_all_at(&len.to_be_bytes(), 4 + len as u64).unwrap();
+    file.write_all_at(b"\x00", 8 + len as u64).unwrap();
+    drop(file);
+
+    let mut str = setup_existing(name);
+    str.append(b"Hello, world1").unwrap();
+
+    assert_eq!(str.count(), 2);
+
+    str.seek(SeekFrom::End(-2)).unwrap();
+
+    assert_eq!(str.byte_position(), 0);
+    assert_eq!(str.read_range(0, 5).unwrap(), b"Hello".to_vec());
+
+    str.seek(SeekFrom::Start(1)).unwrap();
+
+    assert_eq!(str.read().unwrap(), b"Hello, world1".to_vec());
+
+    drop(str);
+    fs::remove_file(path).unwrap();
+}
@@ -0,0 +1,129 @@
+use std::{fs::{self, OpenOptions}, io::{SeekFrom, Write as _}, path::Path, thread, time::{Duration, Instant}};
+
+use objstr::{api::{Meta, ObjStr as _}, file::FileObjStr};
+
+fn setup(name: &str) -> FileObjStr {
+    let name = format!(".test/file/follow_test/{}.bin", name);
+    let path = Path::new(
+        name.as_str()
+    );
+    if path.exists() {
+        std::fs::remove_file(path).unwrap();
+    }
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    FileObjStr::new(path).unwrap()
+}
+
+fn teardown(name: &str) {
+    std::fs::remove_file(
+        format!(".test/file/follow_test/{}.bin", name).as_str()
+    ).unwrap();
+}
+
+#[test]
+fn follow_existing_and_new() {
+    let mut str = setup("follow_existing_and_new");
+    str.append(b"Hello").unwrap();
+    str.append_with_meta(b"World", &Meta::now()).unwrap();
+
+    str.seek(SeekFrom::Start(0)).unwrap();
+    let mut follow = str.follow().unwrap()
+        .poll_interval(Duration::from_millis(5))
+        .timeout(Duration::from_secs(5));
+
+    assert_eq!(follow.next().unwrap().unwrap(), b"Hello");
+    assert_eq!(follow.next().unwrap().unwrap(), b"World");
+
+    let writer = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(50));
+        str.append(b"Later").unwrap();
+    });
+
+    assert_eq!(follow.next().unwrap().unwrap(), b"Later");
+    writer.join().unwrap();
+
+    teardown("follow_existing_and_new");
+}
+
+#[test]
+fn follow_from_cursor() {
+    let mut str = setup("follow_from_cursor");
+    str.append(b"Hello").unwrap();
+    str.append(b"World").unwrap();
+
+    str.seek(SeekFrom::End(1)).unwrap();
+    let mut follow = str.follow().unwrap();
+    assert_eq!(follow.try_next().unwrap().unwrap(), b"World");
+    assert_eq!(follow.try_next().unwrap(), None);
+
+    teardown("follow_from_cursor");
+}
+
+#[test]
+fn follow_skips_partial_frames() {
+    let mut str = setup("follow_skips_partial_frames");
+    let mut follow = str.follow().unwrap();
+
+    let path = ".test/file/follow_test/follow_skips_partial_frames.bin";
+    let mut file = OpenOptions::new().write(true).open(path).unwrap();
+
+    file.write_all(&[0, 0, 0, 5, b'H', b'e']).unwrap();
+    assert_eq!(follow.try_next().unwrap(), None);
+
+    file.write_all(&[b'l', b'l', b'o', 0, 0, 0, 5]).unwrap();
+    assert_eq!(follow.try_next().unwrap(), None);
+
+    file.write_all(&[0]).unwrap();
+    assert_eq!(follow.try_next().unwrap().unwrap(), b"Hello");
+
+    teardown("follow_skips_partial_frames");
+}
+
+#[test]
+fn follow_timeout() {
+    let mut str = setup("follow_timeout");
+    str.append(b"Hello").unwrap();
+    let mut follow = str.follow().unwrap()
+        .poll_interval(Duration::from_millis(5))
+        .timeout(Duration::from_millis(30));
+
+    let start = Instant::now();
+    assert!(follow.next().is_none());
+    assert!(start.elapsed() >= Duration::from_millis(30));
+
+    teardown("follow_timeout");
+}
+
+#[test]
+fn follow_cut_behind() {
+    let mut str = setup("follow_cut_behind");
+    str.append(b"Hello").unwrap();
+    str.append(b"World").unwrap();
+
+    str.seek(SeekFrom::End(0)).unwrap();
+    let mut follow = str.follow().unwrap();
+
+    str.seek(SeekFrom::Start(0)).unwrap();
+    str.cut().unwrap();
+    assert!(follow.try_next().is_err());
+
+    teardown("follow_cut_behind");
+}
+
+#[test]
+fn follow_cut_regrown() {
+    let mut str = setup("follow_cut_regrown");
+    str.append(b"Hello").unwrap();
+    str.append(b"World").unwrap();
+
+    str.seek(SeekFrom::End(0)).unwrap();
+    let mut follow = str.follow().unwrap();
+
+    str.seek(SeekFrom::Start(0)).unwrap();
+    str.cut().unwrap();
+    str.append(b"Hello, world1").unwrap();
+    str.append(b"Hello, world2").unwrap();
+    assert!(follow.try_next().is_err());
+
+    teardown("follow_cut_regrown");
+}
@@ -1,2 +1,11 @@
 
-pub mod file_obj_str_test;
\ No newline at end of file
+pub mod file_obj_str_test;
+pub mod follow_test;
+pub mod observer_test;
+#[cfg(feature = "rayon")]
+pub mod par_iter_test;
+pub mod replication_test;
+pub mod retention_test;
+pub mod salvage_test;
+pub mod snapshot_test;
+pub mod split_test;
\ No newline at end of file
@@ -0,0 +1,154 @@
+use std::{fs, io::SeekFrom, path::Path, sync::{Arc, Mutex}};
+
+use objstr::{api::{Meta, ObjStr as _}, file::{Event, FileObjStr, Retention}};
+
+fn setup(name: &str) -> FileObjStr {
+    let name = format!(".test/file/observer_test/{}.bin", name);
+    let path = Path::new(
+        name.as_str()
+    );
+    if path.exists() {
+        std::fs::remove_file(path).unwrap();
+    }
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    FileObjStr::new(path).unwrap()
+}
+
+fn teardown(name: &str) {
+    std::fs::remove_file(
+        format!(".test/file/observer_test/{}.bin", name).as_str()
+    ).unwrap();
+}
+
+#[test]
+fn append_events() {
+    let mut str = setup("append_events");
+    str.append(b"before").unwrap();
+
+    let events = str.events();
+    str.append(b"Hello").unwrap();
+    str.append_with_meta(b"World", &Meta::now()).unwrap();
+    str.append_batch([b"a".as_slice(), b"bc"]).unwrap();
+
+    let mut appender = str.buffered_appender(1024);
+    appender.append(b"def").unwrap();
+    drop(appender);
+
+    assert_eq!(
+        events.try_iter().collect::<Vec<_>>(),
+        vec![
+            Event::Append { index: 1, len: 5 },
+            Event::Append { index: 2, len: 5 },
+            Event::Append { index: 3, len: 1 },
+            Event::Append { index: 4, len: 2 },
+            Event::Append { index: 5, len: 3 }
+        ]
+    );
+
+    teardown("append_events");
+}
+
+#[test]
+fn overwrite_cut_events() {
+    let mut str = setup("overwrite_cut_events");
+    str.append(b"Hello").unwrap();
+    str.append(b"World").unwrap();
+    str.append(b"!").unwrap();
+
+    let events = str.events();
+
+    str.seek(SeekFrom::Start(1)).unwrap();
+    str.patch(0, b"w").unwrap();
+    str.overwrite(&[b"wor".as_slice(), b"ld!".as_slice()], 2).unwrap();
+    str.seek(SeekFrom::Start(1)).unwrap();
+    str.cut().unwrap();
+    assert_eq!(str.count(), 1);
+
+    assert_eq!(
+        events.try_iter().collect::<Vec<_>>(),
+        vec![
+            Event::Overwrite { range: 1..2, replaced: 1 },
+            Event::Overwrite { range: 1..3, replaced: 2 },
+            Event::Cut { len: 1 }
+        ]
+    );
+
+    teardown("overwrite_cut_events");
+}
+
+#[test]
+fn walked_index_events() {
+    let mut str = setup("walked_index_events");
+    for data in [b"a", b"b", b"c", b"d", b"e"] {
+        str.append(data).unwrap();
+    }
+
+    let events = str.events();
+
+    str.seek(SeekFrom::Start(3)).unwrap();
+    str.overwrite(&[b"x".as_slice()], 1).unwrap();
+    str.seek(SeekFrom::Start(1)).unwrap();
+    str.overwrite(&[b"y".as_slice()], 1).unwrap();
+    str.trim_front(1).unwrap();
+    str.seek(SeekFrom::Start(2)).unwrap();
+    str.overwrite(&[b"z".as_slice()], 1).unwrap();
+    str.cut().unwrap();
+    str.append(b"f").unwrap();
+    assert_eq!(str.count(), 4);
+
+    assert_eq!(
+        events.try_iter().collect::<Vec<_>>(),
+        vec![
+            Event::Overwrite { range: 3..4, replaced: 1 },
+            Event::Overwrite { range: 1..2, replaced: 1 },
+            Event::Trim { objs: 1 },
+            Event::Overwrite { range: 2..3, replaced: 1 },
+            Event::Cut { len: 3 },
+            Event::Append { index: 3, len: 1 }
+        ]
+    );
+
+    teardown("walked_index_events");
+}
+
+#[test]
+fn trim_and_compact_events() {
+    let mut str = setup("trim_and_compact_events");
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let sink = seen.clone();
+    str.subscribe(move |event| sink.lock().unwrap().push(event.clone()));
+
+    str.set_retention(Some(Retention::new().max_objects(2).slack(0)));
+    for data in [b"a", b"b", b"c"] {
+        str.append(data).unwrap();
+    }
+    str.set_retention(None);
+    str.compact(|data| (data != b"b").then_some(data)).unwrap();
+
+    assert_eq!(
+        *seen.lock().unwrap(),
+        vec![
+            Event::Append { index: 0, len: 1 },
+            Event::Append { index: 1, len: 1 },
+            Event::Append { index: 2, len: 1 },
+            Event::Trim { objs: 1 },
+            Event::Rewrite { len: 1 }
+        ]
+    );
+
+    teardown("trim_and_compact_events");
+}
+
+#[test]
+fn dropped_receiver_unsubscribes() {
+    let mut str = setup("dropped_receiver_unsubscribes");
+    drop(str.events());
+
+    str.append(b"Hello").unwrap();
+    let events = str.events();
+    str.append(b"World").unwrap();
+
+    assert_eq!(events.try_iter().collect::<Vec<_>>(), vec![Event::Append { index: 1, len: 5 }]);
+
+    teardown("dropped_receiver_unsubscribes");
+}
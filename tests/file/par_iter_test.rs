@@ -0,0 +1,95 @@
+use std::{fs, path::Path};
+
+use objstr::{api::{Meta, ObjStr as _}, file::FileObjStr};
+use rayon::iter::ParallelIterator as _;
+
+fn setup(name: &str) -> FileObjStr {
+    let name = format!(".test/file/par_iter_test/{}.bin", name);
+    let path = Path::new(
+        name.as_str()
+    );
+    if path.exists() {
+        std::fs::remove_file(
+            name.as_str()
+        ).unwrap();
+    }
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    FileObjStr::new(path).unwrap()
+}
+
+fn teardown(name: &str) -> Vec<u8> {
+    let bytes = std::fs::read(
+        format!(".test/file/par_iter_test/{}.bin", name).as_str()
+    ).unwrap();
+    std::fs::remove_file(
+        format!(".test/file/par_iter_test/{}.bin", name).as_str()
+    ).unwrap();
+    bytes
+}
+
+fn objects() -> Vec<Vec<u8>> {
+    (0..1000u32).map(|i| format!("Hello, world{}", i).into_bytes()).collect()
+}
+
+#[test]
+fn test_par_iter_objects() {
+    let mut str = setup("test_par_iter_objects");
+
+    for (i, obj) in objects().iter().enumerate() {
+        if i % 3 == 0 {
+            str.append_with_meta(obj, &Meta::now()).unwrap();
+        } else {
+            str.append(obj).unwrap();
+        }
+    }
+
+    let res = str.par_iter_with(7).unwrap().objects().collect::<Result<Vec<_>, _>>().unwrap();
+
+    assert_eq!(res, objects());
+
+    let _ = teardown("test_par_iter_objects");
+}
+
+#[test]
+fn test_par_iter_ranges() {
+    let mut str = setup("test_par_iter_ranges");
+
+    str.append_batch(objects()).unwrap();
+
+    let counts = str.par_iter_with(4).unwrap().ranges().map(|range| range.count()).collect::<Vec<_>>();
+
+    assert!(counts.len() > 1);
+    assert_eq!(counts.iter().sum::<usize>(), 1000);
+
+    let _ = teardown("test_par_iter_ranges");
+}
+
+#[test]
+fn test_par_iter_empty() {
+    let mut str = setup("test_par_iter_empty");
+
+    assert_eq!(str.par_iter().unwrap().objects().count(), 0);
+
+    let _ = teardown("test_par_iter_empty");
+}
+
+#[test]
+fn test_par_iter_embedded_frames() {
+    let mut str = setup("test_par_iter_embedded_frames");
+
+    let embedded = [0, 0, 0, 4, b'a', b'b', b'c', b'd', 0, 0, 0, 4].repeat(1000);
+
+    str.append(b"Hello, world1").unwrap();
+    str.append(&embedded).unwrap();
+    str.append(b"Hello, world2").unwrap();
+
+    let res = str.par_iter_with(4).unwrap().objects().collect::<Result<Vec<_>, _>>();
+
+    assert_eq!(res.unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+
+    let res = str.par_iter_with(1).unwrap().objects().collect::<Result<Vec<_>, _>>().unwrap();
+
+    assert_eq!(res, vec![b"Hello, world1".to_vec(), embedded, b"Hello, world2".to_vec()]);
+
+    let _ = teardown("test_par_iter_embedded_frames");
+}
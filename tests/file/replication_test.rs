@@ -0,0 +1,199 @@
+use std::{fs, io::{SeekFrom, Write}, path::Path};
+
+use objstr::{api::{Meta, ObjStr as _}, file::{apply, FileObjStr, Replicator, Retention}};
+
+fn setup(name: &str) -> (FileObjStr, FileObjStr) {
+    let mut strs = Vec::new();
+    for ext in ["primary", "follower"] {
+        let name = format!(".test/file/replication_test/{}.{}", name, ext);
+        let path = Path::new(
+            name.as_str()
+        );
+        if path.exists() {
+            std::fs::remove_file(path).unwrap();
+        }
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        strs.push(FileObjStr::new(path).unwrap());
+    }
+    let follower = strs.pop().unwrap();
+    (strs.pop().unwrap(), follower)
+}
+
+fn assert_same(primary: &FileObjStr, follower: &FileObjStr) {
+    assert_eq!(fs::read(primary.path()).unwrap(), fs::read(follower.path()).unwrap());
+}
+
+fn teardown(primary: FileObjStr, follower: FileObjStr) {
+    for str in [primary, follower] {
+        std::fs::remove_file(str.path()).unwrap();
+    }
+}
+
+#[test]
+fn replicate_appends() {
+    let (mut primary, mut follower) = setup("replicate_appends");
+    primary.append(b"existing").unwrap();
+
+    let mut replicator = Replicator::new(&mut primary, 0);
+    replicator.replicate_to(&mut primary, &mut follower).unwrap();
+    assert_same(&primary, &follower);
+
+    primary.append(b"Hello").unwrap();
+    primary.append_with_meta(b"World", &Meta::now()).unwrap();
+    replicator.replicate_to(&mut primary, &mut follower).unwrap();
+    assert_same(&primary, &follower);
+
+    replicator.replicate_to(&mut primary, &mut follower).unwrap();
+    assert_same(&primary, &follower);
+
+    teardown(primary, follower);
+}
+
+#[test]
+fn replicate_mutations() {
+    let (mut primary, mut follower) = setup("replicate_mutations");
+    let mut replicator = Replicator::new(&mut primary, 0);
+
+    for data in [b"a", b"b", b"c", b"d"] {
+        primary.append(data).unwrap();
+    }
+    replicator.replicate_to(&mut primary, &mut follower).unwrap();
+
+    primary.seek(SeekFrom::Start(1)).unwrap();
+    primary.overwrite(&[b"x".as_slice()], 1).unwrap();
+    primary.trim_front(1).unwrap();
+    replicator.replicate_to(&mut primary, &mut follower).unwrap();
+    assert_same(&primary, &follower);
+
+    primary.seek(SeekFrom::Start(1)).unwrap();
+    primary.cut().unwrap();
+    replicator.replicate_to(&mut primary, &mut follower).unwrap();
+    assert_same(&primary, &follower);
+    assert_eq!(follower.count(), 1);
+
+    primary.append(b"e").unwrap();
+    primary.compact(|data| Some([data.as_slice(), b"!"].concat())).unwrap();
+    replicator.replicate_to(&mut primary, &mut follower).unwrap();
+    assert_same(&primary, &follower);
+
+    teardown(primary, follower);
+}
+
+#[test]
+fn replicate_over_pipe_and_resume() {
+    let (mut primary, mut follower) = setup("replicate_over_pipe_and_resume");
+    primary.append_batch([vec![1; 700 * 1024], vec![2; 700 * 1024], vec![3; 10]]).unwrap();
+
+    let mut pipe = Vec::new();
+    Replicator::new(&mut primary, 0).ship(&mut primary, &mut pipe).unwrap();
+    assert_eq!(apply(&mut follower, &mut pipe.as_slice()).unwrap(), 2);
+    assert_same(&primary, &follower);
+
+    primary.append(b"after restart").unwrap();
+
+    let mut pipe = Vec::new();
+    Replicator::new(&mut primary, follower.count()).ship(&mut primary, &mut pipe).unwrap();
+    assert_eq!(apply(&mut follower, &mut pipe.as_slice()).unwrap(), 1);
+    assert_same(&primary, &follower);
+
+    teardown(primary, follower);
+}
+
+#[test]
+fn detect_divergence_and_damage() {
+    let (mut primary, mut follower) = setup("detect_divergence_and_damage");
+    primary.append(b"Hello").unwrap();
+    primary.append(b"World").unwrap();
+    follower.append(b"Other").unwrap();
+
+    let mut pipe = Vec::new();
+    Replicator::new(&mut primary, 1).ship(&mut primary, &mut pipe).unwrap();
+    assert!(apply(&mut follower, &mut pipe.as_slice()).is_err());
+    assert_eq!(follower.count(), 1);
+
+    let mut pipe = Vec::new();
+    Replicator::new(&mut primary, 0).ship(&mut primary, &mut pipe).unwrap();
+    let last = pipe.len() - 1;
+    pipe[last] ^= 1;
+    assert!(apply(&mut follower, &mut pipe.as_slice()).is_err());
+
+    pipe[last] ^= 1;
+    apply(&mut follower, &mut pipe.as_slice()).unwrap();
+    assert_same(&primary, &follower);
+
+    teardown(primary, follower);
+}
+
+#[test]
+fn reject_oversized_messages() {
+    let (mut primary, mut follower) = setup("reject_oversized_messages");
+    primary.append(b"Hello").unwrap();
+
+    let mut pipe = Vec::new();
+    Replicator::new(&mut primary, 0).ship(&mut primary, &mut pipe).unwrap();
+
+    let mut oversized = pipe.clone();
+    oversized[17..21].copy_from_slice(&u32::MAX.to_be_bytes());
+    assert_eq!(apply(&mut follower, &mut oversized.as_slice()).unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+
+    let mut truncated = pipe.clone();
+    truncated[17..21].copy_from_slice(&1_000_000u32.to_be_bytes());
+    assert_eq!(apply(&mut follower, &mut truncated.as_slice()).unwrap_err().kind(), std::io::ErrorKind::UnexpectedEof);
+    assert_eq!(follower.count(), 0);
+
+    teardown(primary, follower);
+}
+
+#[test]
+fn replicate_with_retention() {
+    let (mut primary, mut follower) = setup("replicate_with_retention");
+    let mut replicator = Replicator::new(&mut primary, 0);
+    primary.set_retention(Some(Retention::new().max_objects(1)));
+
+    for data in [b"a", b"b", b"c"] {
+        primary.append(data).unwrap();
+    }
+    replicator.replicate_to(&mut primary, &mut follower).unwrap();
+    assert_same(&primary, &follower);
+
+    for data in [b"d", b"e"] {
+        primary.append(data).unwrap();
+    }
+    replicator.replicate_to(&mut primary, &mut follower).unwrap();
+    assert_same(&primary, &follower);
+
+    teardown(primary, follower);
+}
+
+struct Broken;
+
+impl Write for Broken {
+    fn write(&mut self, _: &[u8]) -> std::io::Result<usize> {
+        Err(std::io::Error::other("Connection lost."))
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn replicate_after_failed_ship() {
+    let (mut primary, mut follower) = setup("replicate_after_failed_ship");
+    let mut replicator = Replicator::new(&mut primary, 0);
+
+    for data in [b"a", b"b", b"c"] {
+        primary.append(data).unwrap();
+    }
+    replicator.replicate_to(&mut primary, &mut follower).unwrap();
+
+    primary.seek(SeekFrom::Start(1)).unwrap();
+    primary.overwrite(&[b"x".as_slice()], 1).unwrap();
+    primary.trim_front(1).unwrap();
+    assert!(replicator.ship(&mut primary, &mut Broken).is_err());
+
+    replicator.replicate_to(&mut primary, &mut follower).unwrap();
+    assert_same(&primary, &follower);
+
+    teardown(primary, follower);
+}
@@ -0,0 +1,152 @@
+use std::{fs, io::SeekFrom, path::Path, time::{Duration, SystemTime, UNIX_EPOCH}};
+
+use objstr::{api::{Meta, ObjStr as _}, file::{Event, FileObjStr, Retention}};
+
+fn setup(name: &str) -> FileObjStr {
+    let name = format!(".test/file/retention_test/{}.bin", name);
+    let path = Path::new(
+        name.as_str()
+    );
+    if path.exists() {
+        std::fs::remove_file(
+            name.as_str()
+        ).unwrap();
+    }
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    FileObjStr::new(path).unwrap()
+}
+
+fn teardown(name: &str) -> Vec<u8> {
+    let bytes = std::fs::read(
+        format!(".test/file/retention_test/{}.bin", name).as_str()
+    ).unwrap();
+    std::fs::remove_file(
+        format!(".test/file/retention_test/{}.bin", name).as_str()
+    ).unwrap();
+    bytes
+}
+
+fn timestamp(data: &[u8]) -> Option<SystemTime> {
+    let secs = u64::from_be_bytes(data.get(..8)?.try_into().ok()?);
+    Some(UNIX_EPOCH + Duration::from_secs(secs))
+}
+
+fn stamped(secs: u64) -> Vec<u8> {
+    u64::to_be_bytes(secs).to_vec()
+}
+
+#[test]
+fn test_retain_max_objects() {
+    let mut str = setup("test_retain_max_objects");
+
+    str.append(b"Hello, world1").unwrap();
+    str.append(b"Hello, world2").unwrap();
+    str.append(b"Hello, world3").unwrap();
+
+    assert_eq!(str.retain(&Retention::new().max_objects(1)).unwrap(), 2);
+    assert_eq!(str.count(), 1);
+
+    let bytes = teardown("test_retain_max_objects");
+
+    assert_eq!(bytes, b"\x00\x00\x00\x0dHello, world3\x00\x00\x00\x0d\x00".to_vec());
+}
+
+#[test]
+fn test_retain_max_bytes() {
+    let mut str = setup("test_retain_max_bytes");
+
+    str.append(b"Hello, world1").unwrap();
+    str.append(b"Hello, world2").unwrap();
+    str.append(b"Hello, world3").unwrap();
+
+    assert_eq!(str.retain(&Retention::new().max_bytes(2 * 21)).unwrap(), 1);
+
+    str.seek(SeekFrom::Start(0)).unwrap();
+
+    assert_eq!(str.read().unwrap(), b"Hello, world2".to_vec());
+
+    let _ = teardown("test_retain_max_bytes");
+}
+
+#[test]
+fn test_retain_max_age() {
+    let mut str = setup("test_retain_max_age");
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+
+    str.append(&stamped(now - 7200)).unwrap();
+    str.append(&stamped(now - 3600)).unwrap();
+    str.append(&stamped(now)).unwrap();
+
+    let retention = Retention::new().max_age(Duration::from_secs(1800)).timestamp(timestamp);
+
+    assert_eq!(str.retain(&retention).unwrap(), 2);
+
+    str.seek(SeekFrom::Start(0)).unwrap();
+
+    assert_eq!(str.read().unwrap(), stamped(now));
+
+    let _ = teardown("test_retain_max_age");
+}
+
+#[test]
+fn test_retain_max_age_meta() {
+    let mut str = setup("test_retain_max_age_meta");
+
+    let mut old = Meta::new();
+    old.created = Some(SystemTime::now().duration_since(UNIX_EPOCH).unwrap() - Duration::from_secs(3600));
+
+    str.append_with_meta(b"Hello, world1", &old).unwrap();
+    str.append_with_meta(b"Hello, world2", &Meta::now()).unwrap();
+
+    assert_eq!(str.retain(&Retention::new().max_age(Duration::from_secs(1800))).unwrap(), 1);
+
+    str.seek(SeekFrom::Start(0)).unwrap();
+
+    assert_eq!(str.read().unwrap(), b"Hello, world2".to_vec());
+
+    let _ = teardown("test_retain_max_age_meta");
+}
+
+#[test]
+fn test_retain_slack() {
+    let mut str = setup("test_retain_slack");
+
+    str.set_retention(Some(Retention::new().max_objects(2).slack(100)));
+
+    str.append(b"Hello, world1").unwrap();
+    str.append(b"Hello, world2").unwrap();
+    str.append(b"Hello, world3").unwrap();
+    str.append(b"Hello, world4").unwrap();
+
+    assert_eq!(str.count(), 4);
+
+    str.append(b"Hello, world5").unwrap();
+
+    assert_eq!(str.count(), 2);
+
+    str.seek(SeekFrom::Start(0)).unwrap();
+
+    assert_eq!(str.read().unwrap(), b"Hello, world4".to_vec());
+
+    let _ = teardown("test_retain_slack");
+}
+
+#[test]
+fn test_retain_default_slack() {
+    let mut str = setup("test_retain_default_slack");
+    let events = str.events();
+
+    str.set_retention(Some(Retention::new().max_objects(100)));
+
+    for i in 0..1000u32 {
+        str.append(&i.to_be_bytes()).unwrap();
+        assert!(str.count() <= 110);
+    }
+
+    let trims = events.try_iter().filter(|event| matches!(event, Event::Trim { .. })).count();
+
+    assert!(trims <= 1000 / 10, "{} trims", trims);
+
+    let _ = teardown("test_retain_default_slack");
+}
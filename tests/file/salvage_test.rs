@@ -0,0 +1,114 @@
+use std::{fs, ops::Range, path::Path};
+
+use objstr::{api::{Meta, ObjStr as _}, file::{salvage, FileObjStr, Salvage}};
+
+fn setup(name: &str) -> FileObjStr {
+    let name = format!(".test/file/salvage_test/{}.bin", name);
+    let path = Path::new(
+        name.as_str()
+    );
+    for path in [path.to_path_buf(), path.with_extension("salvaged")] {
+        if path.exists() {
+            std::fs::remove_file(&path).unwrap();
+        }
+    }
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    FileObjStr::new(path).unwrap()
+}
+
+fn damage(name: &str, f: impl FnOnce(&mut Vec<u8>)) {
+    let path = format!(".test/file/salvage_test/{}.bin", name);
+    let mut bytes = fs::read(&path).unwrap();
+    f(&mut bytes);
+    fs::write(&path, bytes).unwrap();
+}
+
+fn run(name: &str) -> Salvage {
+    let path = format!(".test/file/salvage_test/{}.bin", name);
+    salvage(Path::new(&path), Path::new(&path).with_extension("salvaged").as_path()).unwrap()
+}
+
+fn teardown(name: &str) -> Vec<u8> {
+    let path = format!(".test/file/salvage_test/{}.bin", name);
+    std::fs::remove_file(&path).unwrap();
+    let salvaged = Path::new(&path).with_extension("salvaged");
+    let bytes = std::fs::read(&salvaged).unwrap();
+    std::fs::remove_file(&salvaged).unwrap();
+    bytes
+}
+
+#[test]
+fn test_salvage_intact() {
+    let mut str = setup("test_salvage_intact");
+
+    str.append(b"Hello, world1").unwrap();
+    str.append_with_meta(b"Hello, world2", &Meta::now()).unwrap();
+
+    let report = run("test_salvage_intact");
+
+    assert_eq!(report, Salvage { recovered: 2, lost: vec![], suspicious: vec![] });
+
+    drop(str);
+    let bytes = teardown("test_salvage_intact");
+
+    assert_eq!(bytes.len(), 21 + 44 + 1);
+}
+
+#[test]
+fn test_salvage_damaged_middle() {
+    let mut str = setup("test_salvage_damaged_middle");
+
+    str.append(b"Hello, world1").unwrap();
+    str.append(b"Hello, world2").unwrap();
+    str.append(b"Hello, world3").unwrap();
+
+    damage("test_salvage_damaged_middle", |bytes| bytes[21 + 1] = 0x7f);
+
+    let report = run("test_salvage_damaged_middle");
+
+    assert_eq!(report.recovered, 2);
+    assert_eq!(report.lost, vec![Range { start: 21, end: 42 }]);
+
+    drop(str);
+    let bytes = teardown("test_salvage_damaged_middle");
+
+    assert_eq!(bytes, b"\x00\x00\x00\x0dHello, world1\x00\x00\x00\x0d\x00\x00\x00\x0dHello, world3\x00\x00\x00\x0d\x00".to_vec());
+}
+
+#[test]
+fn test_salvage_truncated() {
+    let mut str = setup("test_salvage_truncated");
+
+    str.append(b"Hello, world1").unwrap();
+    str.append(b"Hello, world2").unwrap();
+
+    damage("test_salvage_truncated", |bytes| bytes.truncate(30));
+
+    let report = run("test_salvage_truncated");
+
+    assert_eq!(report.recovered, 1);
+    assert_eq!(report.lost, vec![Range { start: 21, end: 30 }]);
+
+    drop(str);
+    let _ = teardown("test_salvage_truncated");
+}
+
+#[test]
+fn test_salvage_zeroed() {
+    let mut str = setup("test_salvage_zeroed");
+
+    str.append(b"Hello, world1").unwrap();
+    str.append(b"Hello, world2 -- Hello, world2!!").unwrap();
+    str.append(b"Hello, world3").unwrap();
+
+    damage("test_salvage_zeroed", |bytes| bytes[21..61].fill(0));
+
+    let report = run("test_salvage_zeroed");
+
+    assert_eq!(report.recovered, 2 + 5);
+    assert_eq!(report.lost, vec![]);
+    assert_eq!(report.suspicious, vec![Range { start: 21, end: 61 }]);
+
+    drop(str);
+    let _ = teardown("test_salvage_zeroed");
+}
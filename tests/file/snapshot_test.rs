@@ -0,0 +1,119 @@
+use std::{fs, io::SeekFrom, path::Path};
+
+use objstr::{api::ObjStr as _, file::FileObjStr};
+
+fn setup(name: &str) -> FileObjStr {
+    let name = format!(".test/file/snapshot_test/{}.bin", name);
+    let path = Path::new(
+        name.as_str()
+    );
+    if path.exists() {
+        std::fs::remove_file(
+            name.as_str()
+        ).unwrap();
+    }
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    FileObjStr::new(path).unwrap()
+}
+
+fn teardown(name: &str) -> Vec<u8> {
+    let bytes = std::fs::read(
+        format!(".test/file/snapshot_test/{}.bin", name).as_str()
+    ).unwrap();
+    std::fs::remove_file(
+        format!(".test/file/snapshot_test/{}.bin", name).as_str()
+    ).unwrap();
+    bytes
+}
+
+#[test]
+fn test_snapshot_append() {
+    let mut str = setup("test_snapshot_append");
+
+    str.append(b"Hello, world1").unwrap();
+    str.append(b"Hello, world2").unwrap();
+
+    let snapshot = str.snapshot().unwrap();
+
+    str.append(b"Hello, world3").unwrap();
+
+    assert_eq!(snapshot.object_count(), 2);
+    assert_eq!(
+        snapshot.collect::<Result<Vec<_>, _>>().unwrap(),
+        vec![b"Hello, world1".to_vec(), b"Hello, world2".to_vec()]
+    );
+
+    let _ = teardown("test_snapshot_append");
+}
+
+#[test]
+fn test_snapshot_seek() {
+    let mut str = setup("test_snapshot_seek");
+
+    str.append(b"Hello, world1").unwrap();
+    str.append(b"Hello, world2").unwrap();
+
+    let mut snapshot = str.snapshot().unwrap();
+
+    str.append(b"Hello, world3").unwrap();
+
+    snapshot.seek(SeekFrom::End(-1)).unwrap();
+
+    assert_eq!(snapshot.read().unwrap(), b"Hello, world2".to_vec());
+    assert!(snapshot.read().is_err());
+    assert!(snapshot.seek(SeekFrom::Start(3)).is_err());
+
+    let _ = teardown("test_snapshot_seek");
+}
+
+#[test]
+fn test_snapshot_cut() {
+    let mut str = setup("test_snapshot_cut");
+
+    str.append(b"Hello, world1").unwrap();
+    str.append(b"Hello, world2").unwrap();
+
+    let mut snapshot = str.snapshot().unwrap();
+
+    str.seek(SeekFrom::Start(1)).unwrap();
+    str.cut().unwrap();
+    str.append(b"Hello, world3 -- Hello, world3").unwrap();
+
+    assert_eq!(snapshot.read().unwrap_err().kind(), std::io::ErrorKind::Other);
+
+    let _ = teardown("test_snapshot_cut");
+}
+
+#[test]
+fn test_snapshot_cut_regrown() {
+    let mut str = setup("test_snapshot_cut_regrown");
+
+    str.append(b"Hello, world1").unwrap();
+    str.append(b"Hello, world2").unwrap();
+
+    let mut snapshot = str.snapshot().unwrap();
+
+    str.seek(SeekFrom::Start(1)).unwrap();
+    str.cut().unwrap();
+    str.append(b"Hello, world3").unwrap();
+
+    assert_eq!(snapshot.read().unwrap_err().kind(), std::io::ErrorKind::Other);
+
+    let _ = teardown("test_snapshot_cut_regrown");
+}
+
+#[test]
+fn test_snapshot_overwrite() {
+    let mut str = setup("test_snapshot_overwrite");
+
+    str.append(b"Hello, world1").unwrap();
+
+    let mut snapshot = str.snapshot().unwrap();
+
+    str.seek(SeekFrom::Start(0)).unwrap();
+    str.overwrite(&[b"Hello, world2"], 1).unwrap();
+
+    assert_eq!(snapshot.seek(SeekFrom::Start(0)).unwrap_err().kind(), std::io::ErrorKind::Other);
+
+    let _ = teardown("test_snapshot_overwrite");
+}
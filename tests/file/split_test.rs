@@ -0,0 +1,149 @@
+use std::{fs, io::{ErrorKind, SeekFrom}, path::{Path, PathBuf}};
+
+use objstr::{api::{Meta, ObjStr as _}, file::{Event, FileObjStr}};
+
+fn path(name: &str) -> PathBuf {
+    let path = PathBuf::from(format!(".test/file/split_test/{}.bin", name));
+    if path.exists() {
+        fs::remove_file(&path).unwrap();
+    }
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    path
+}
+
+fn path_of(name: &str) -> PathBuf {
+    Path::new(&format!(".test/file/split_test/{}.bin", name)).to_path_buf()
+}
+
+fn setup(name: &str, objs: &[&[u8]]) -> FileObjStr {
+    let mut str = FileObjStr::new(&path(name)).unwrap();
+    for obj in objs {
+        str.append(obj).unwrap();
+    }
+    str.seek(SeekFrom::Start(0)).unwrap();
+    str
+}
+
+fn objects(str: &mut FileObjStr) -> Vec<Vec<u8>> {
+    str.seek(SeekFrom::Start(0)).unwrap();
+    let mut objs = Vec::new();
+    loop {
+        match str.read() {
+            Ok(data) => objs.push(data),
+            Err(e) => {
+                assert_eq!(e.kind(), ErrorKind::UnexpectedEof);
+                return objs;
+            }
+        }
+    }
+}
+
+fn teardown(names: &[&str]) {
+    for name in names {
+        fs::remove_file(format!(".test/file/split_test/{}.bin", name)).unwrap();
+    }
+}
+
+#[test]
+fn split_at() {
+    let mut str = setup("split_at", &[b"a", b"bb", b"", b"dddd"]);
+    let dest = path("split_at_dest");
+
+    str.seek(SeekFrom::Start(1)).unwrap();
+    let mut tail = str.split_at(2, &dest).unwrap();
+
+    assert_eq!(str.read().unwrap(), b"bb");
+    assert_eq!(str.read().err().unwrap().kind(), ErrorKind::UnexpectedEof);
+    assert_eq!(str.count(), 2);
+    assert_eq!(objects(&mut str), vec![b"a".to_vec(), b"bb".to_vec()]);
+
+    assert_eq!(tail.count(), 2);
+    assert_eq!(objects(&mut tail), vec![b"".to_vec(), b"dddd".to_vec()]);
+    assert_eq!(fs::read(&dest).unwrap(), vec![0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 4, b'd', b'd', b'd', b'd', 0, 0, 0, 4, 0]);
+
+    teardown(&["split_at", "split_at_dest"]);
+}
+
+#[test]
+fn split_at_bounds() {
+    let mut str = setup("split_at_bounds", &[b"a", b"b"]);
+    let dest = path("split_at_bounds_dest");
+
+    assert_eq!(str.split_at(3, &dest).err().unwrap().kind(), ErrorKind::UnexpectedEof);
+    assert!(!dest.exists());
+
+    let mut tail = str.split_at(2, &dest).unwrap();
+    assert_eq!(tail.count(), 0);
+    assert_eq!(objects(&mut str), vec![b"a".to_vec(), b"b".to_vec()]);
+
+    let mut tail = str.split_at(0, &dest).unwrap();
+    assert_eq!(objects(&mut tail), vec![b"a".to_vec(), b"b".to_vec()]);
+    assert_eq!(str.count(), 0);
+    assert_eq!(fs::read(str.path()).unwrap(), vec![0]);
+
+    let src = str.path().to_path_buf();
+    assert_eq!(str.split_at(0, &src).err().unwrap().kind(), ErrorKind::InvalidInput);
+
+    teardown(&["split_at_bounds", "split_at_bounds_dest"]);
+}
+
+#[test]
+fn split_at_events() {
+    let mut str = setup("split_at_events", &[b"a", b"b", b"c"]);
+    let events = str.events();
+
+    let dest = path("split_at_events_dest");
+    let mut tail = FileObjStr::new(&dest).unwrap();
+    tail.append(b"x").unwrap();
+    drop(tail);
+
+    let mut tail = str.split_at(1, &dest).unwrap();
+    assert_eq!(events.try_iter().collect::<Vec<_>>(), vec![Event::Cut { len: 1 }]);
+    assert_eq!(objects(&mut tail), vec![b"x".to_vec(), b"b".to_vec(), b"c".to_vec()]);
+
+    teardown(&["split_at_events", "split_at_events_dest"]);
+}
+
+#[test]
+fn concat() {
+    let mut a = setup("concat_a", &[b"a", b"b"]);
+    a.append_with_meta(b"meta", &Meta::now()).unwrap();
+    setup("concat_b", &[]);
+    setup("concat_c", &[b"c"]);
+
+    let dest = path("concat_dest");
+    let mut out = FileObjStr::new(&dest).unwrap();
+    out.append(b"first").unwrap();
+    drop(out);
+
+    let sources = [path_of("concat_a"), path_of("concat_b"), path_of("concat_c")];
+    let mut out = FileObjStr::concat(&sources, &dest).unwrap();
+
+    assert_eq!(out.count(), 5);
+    assert_eq!(
+        objects(&mut out),
+        vec![b"first".to_vec(), b"a".to_vec(), b"b".to_vec(), b"meta".to_vec(), b"c".to_vec()]
+    );
+    out.seek(SeekFrom::Start(3)).unwrap();
+    assert!(out.read_meta().unwrap().is_some());
+    assert_eq!(*fs::read(&dest).unwrap().last().unwrap(), 0);
+
+    teardown(&["concat_a", "concat_b", "concat_c", "concat_dest"]);
+}
+
+#[test]
+fn concat_invalid() {
+    setup("concat_invalid_a", &[b"a"]);
+    let dest = path("concat_invalid_dest");
+    let err = FileObjStr::concat(&[path_of("concat_invalid_a"), path_of("concat_invalid_a")], &path_of("concat_invalid_a")).err().unwrap();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+
+    let broken = path("concat_invalid_broken");
+    fs::write(&broken, [0, 0, 0, 9, b'x', 0]).unwrap();
+    let err = FileObjStr::concat(&[path_of("concat_invalid_a"), broken], &dest).err().unwrap();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+
+    assert!(!dest.exists());
+
+    teardown(&["concat_invalid_a", "concat_invalid_broken"]);
+}
@@ -0,0 +1,118 @@
+use objstr::{api::ObjStr as _, frame::{BlockDevice as _, BlockObjStr, MemFlash, ERASED}, io::{ErrorKind, SeekFrom}};
+
+fn setup() -> BlockObjStr<MemFlash> {
+    BlockObjStr::new(MemFlash::new(32, 4)).unwrap()
+}
+
+#[test]
+fn test_new_erased() {
+    let mut str = setup();
+    assert_eq!(str.read().unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    assert!(str.into_inner().as_bytes().iter().all(|&b| b == ERASED));
+}
+
+#[test]
+fn test_append_across_blocks() {
+    let mut str = setup();
+    str.append(b"Hello").unwrap();
+    str.append(b"World, spanning").unwrap();
+    str.append(b"!").unwrap();
+
+    let flash = str.into_inner();
+    assert_eq!(&flash.as_bytes()[..8], &[0, 0, 0, 0, 0, 0, 0, 0]);
+    assert_eq!(&flash.as_bytes()[8..21], &[0, 0, 0, 5, b'H', b'e', b'l', b'l', b'o', 0, 0, 0, 5]);
+    assert_eq!(&flash.as_bytes()[32..40], &[0, 0, 0, 1, 0, 0, 0, 0]);
+    assert!((0..4).all(|block| flash.erase_count(block) == 0));
+
+    let mut str = BlockObjStr::new(flash).unwrap();
+    assert_eq!(str.read().unwrap(), b"Hello");
+    assert_eq!(str.read().unwrap(), b"World, spanning");
+    assert_eq!(str.read().unwrap(), b"!");
+    assert_eq!(str.read().unwrap_err().kind(), ErrorKind::UnexpectedEof);
+
+    str.seek(SeekFrom::End(2)).unwrap();
+    assert_eq!(str.read().unwrap(), b"World, spanning");
+    str.seek(SeekFrom::Current(-2)).unwrap();
+    assert_eq!(str.read().unwrap(), b"Hello");
+}
+
+#[test]
+fn test_device_full() {
+    let mut str = setup();
+    str.append(&[1; 64]).unwrap();
+    assert!(str.append(b"").is_err());
+
+    str.seek(SeekFrom::Start(0)).unwrap();
+    assert_eq!(str.read().unwrap(), vec![1; 64]);
+    assert_eq!(str.read().unwrap_err().kind(), ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn test_cut_erases_tail() {
+    let mut str = setup();
+    str.append(b"Hello").unwrap();
+    str.append(b"World, spanning").unwrap();
+    str.append(b"!").unwrap();
+
+    str.seek(SeekFrom::Start(1)).unwrap();
+    str.cut().unwrap();
+    str.append(b"Again").unwrap();
+
+    let flash = str.into_inner();
+    assert_eq!(flash.erase_count(0), 1);
+    assert_eq!(flash.erase_count(1), 1);
+    assert_eq!(flash.erase_count(2), 0);
+    assert_eq!(flash.erase_count(3), 0);
+
+    let mut str = BlockObjStr::new(flash).unwrap();
+    assert_eq!(str.read().unwrap(), b"Hello");
+    assert_eq!(str.read().unwrap(), b"Again");
+    assert_eq!(str.read().unwrap_err().kind(), ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn test_overwrite_rewrites_blocks() {
+    let mut str = setup();
+    str.append(b"Hello").unwrap();
+    str.append(b"World").unwrap();
+
+    str.seek(SeekFrom::Start(1)).unwrap();
+    str.overwrite(&[b"Earth".as_slice()], 1).unwrap();
+    assert_eq!(str.read().unwrap_err().kind(), ErrorKind::UnexpectedEof);
+
+    let mut flash = str.into_inner();
+    assert_eq!(flash.erase_count(0), 1);
+    assert_eq!(flash.erase_count(1), 0);
+    assert_eq!(&flash.as_bytes()[64..72], &[0, 0, 0, 0, 0, 0, 0, 1]);
+    assert!(flash.write_block(2, 8, &[0xff]).is_err());
+
+    let mut str = BlockObjStr::new(flash).unwrap();
+    assert_eq!(str.read().unwrap(), b"Hello");
+    assert_eq!(str.read().unwrap(), b"Earth");
+}
+
+#[test]
+fn test_overwrite_survives_power_loss() {
+    for writes in 0..6 {
+        let mut str = setup();
+        str.append(b"Hello").unwrap();
+        str.append(b"World").unwrap();
+
+        let mut flash = str.into_inner();
+        flash.fail_after(Some(writes));
+
+        let mut str = BlockObjStr::new(flash).unwrap();
+        str.seek(SeekFrom::Start(1)).unwrap();
+        let res = str.overwrite(&[b"Earth".as_slice()], 1);
+
+        let mut flash = str.into_inner();
+        flash.fail_after(None);
+
+        let mut str = BlockObjStr::new(flash).unwrap();
+        assert_eq!(str.read().unwrap(), b"Hello");
+        let second = str.read().unwrap();
+        assert!(second == b"World" || second == b"Earth");
+        assert!(res.is_err() || second == b"Earth");
+        assert_eq!(str.read().unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    }
+}
@@ -0,0 +1,2 @@
+pub mod storage_obj_str_test;
+pub mod block_obj_str_test;
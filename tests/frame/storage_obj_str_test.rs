@@ -0,0 +1,178 @@
+use objstr::{api::ObjStr as _, frame::StorageObjStr, io::{ErrorKind, SeekFrom}};
+
+fn setup() -> StorageObjStr<Vec<u8>> {
+    StorageObjStr::new(Vec::new()).unwrap()
+}
+
+#[test]
+fn test_new_empty() {
+    let str = setup();
+    assert_eq!(str.into_inner(), vec![0]);
+}
+
+#[test]
+fn test_append_read() {
+    let mut str = setup();
+    str.append(b"Hello").unwrap();
+    str.append(b"World").unwrap();
+
+    assert_eq!(str.read().unwrap(), b"Hello");
+    assert_eq!(str.read().unwrap(), b"World");
+    assert_eq!(str.read().unwrap_err().kind(), ErrorKind::UnexpectedEof);
+
+    assert_eq!(
+        str.into_inner(),
+        vec![0, 0, 0, 5, b'H', b'e', b'l', b'l', b'o', 0, 0, 0, 5, 0, 0, 0, 5, b'W', b'o', b'r', b'l', b'd', 0, 0, 0, 5, 0]
+    );
+}
+
+#[test]
+fn test_seek() {
+    let mut str = setup();
+    str.append(b"a").unwrap();
+    str.append(b"b").unwrap();
+    str.append(b"c").unwrap();
+
+    str.seek(SeekFrom::Start(2)).unwrap();
+    assert_eq!(str.read().unwrap(), b"c");
+    str.seek(SeekFrom::End(2)).unwrap();
+    assert_eq!(str.read().unwrap(), b"b");
+    str.seek(SeekFrom::Current(-2)).unwrap();
+    assert_eq!(str.read().unwrap(), b"a");
+
+    assert_eq!(str.seek(SeekFrom::Start(4)).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    assert_eq!(str.seek(SeekFrom::Current(-4)).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn test_len_overwrite() {
+    let mut str = setup();
+    str.append(b"Hello").unwrap();
+    str.append(b"World").unwrap();
+
+    assert_eq!(str.len(0, 1).unwrap(), 13);
+    assert_eq!(str.len(1, 1).unwrap(), 5);
+    assert_eq!(str.len(1, 2).unwrap(), 18);
+    assert!(str.len(1, 0).is_err());
+
+    str.overwrite(&[b"HelloWorl".as_slice(), b"d".as_slice()], 2).unwrap();
+    assert_eq!(str.read().unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    str.seek(SeekFrom::Start(0)).unwrap();
+    assert_eq!(str.read().unwrap(), b"HelloWorl");
+    assert_eq!(str.read().unwrap(), b"d");
+    assert!(str.overwrite(&[b"x".as_slice()], 1).is_err());
+}
+
+#[test]
+fn test_cut() {
+    let mut str = setup();
+    str.append(b"Hello").unwrap();
+    str.append(b"World").unwrap();
+
+    str.seek(SeekFrom::Start(1)).unwrap();
+    str.cut().unwrap();
+    str.append(b"!").unwrap();
+
+    str.seek(SeekFrom::Start(0)).unwrap();
+    assert_eq!(str.read().unwrap(), b"Hello");
+    assert_eq!(str.read().unwrap(), b"!");
+    assert_eq!(str.into_inner().len(), 23);
+}
+
+#[test]
+fn test_detects_length_mismatch() {
+    let mut str = setup();
+    str.append(b"Hello").unwrap();
+
+    let mut bytes = str.into_inner();
+    bytes[12] = 4;
+
+    let mut str = StorageObjStr::new(bytes).unwrap();
+    assert_eq!(str.read().unwrap_err().kind(), ErrorKind::InvalidData);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_same_format_as_file() {
+    use objstr::{api::Meta, file::FileObjStr};
+    use std::{fs, path::Path};
+
+    let path = Path::new(".test/frame/storage_obj_str_test/test_same_format_as_file.bin");
+    if path.exists() {
+        fs::remove_file(path).unwrap();
+    }
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+
+    let mut file = FileObjStr::new(path).unwrap();
+    file.append(b"Hello").unwrap();
+    file.append_with_meta(b"World", &Meta { content_type: Some("text/plain".into()), ..Meta::new() }).unwrap();
+    drop(file);
+
+    let bytes = fs::read(path).unwrap();
+    fs::remove_file(path).unwrap();
+
+    let mut str = StorageObjStr::new(bytes.clone()).unwrap();
+    assert_eq!(str.read().unwrap(), b"Hello");
+    let (data, meta) = str.read_with_meta().unwrap();
+    assert_eq!(data, b"World");
+    assert_eq!(meta.unwrap().content_type.as_deref(), Some("text/plain"));
+
+    let mut str = setup();
+    str.append(b"Hello").unwrap();
+    assert_eq!(str.into_inner(), [&bytes[..13], &[0]].concat());
+}
+
+#[test]
+fn test_read_meta_without_payload() {
+    use objstr::api::Meta;
+
+    let mut meta = Meta::new();
+    meta.content_type = Some("text/plain".into());
+
+    let inner = objstr::frame::encode_meta_inner(&meta.encode().unwrap(), b"Hello");
+
+    let mut bytes = Vec::new();
+    objstr::frame::encode_frame(&mut bytes, &inner, true).unwrap();
+    bytes.push(0);
+
+    let mut str = StorageObjStr::new(bytes).unwrap();
+    assert_eq!(str.read_meta().unwrap(), Some(meta));
+    assert_eq!(str.read().unwrap_err().kind(), ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn test_append_too_long() {
+    let mut str = setup();
+
+    let data = vec![0; objstr::frame::LEN_MASK as usize + 1];
+
+    assert_eq!(str.append(&data).unwrap_err().kind(), ErrorKind::InvalidInput);
+    assert_eq!(str.into_inner(), vec![0]);
+}
+
+#[test]
+fn test_partition_point_probes() {
+    let mut str = setup();
+    for i in 0..1000u32 {
+        str.append(&i.to_be_bytes()).unwrap();
+    }
+
+    let mut probes = 0;
+    let idx = str.partition_point(|obj| {
+        probes += 1;
+        u32::from_be_bytes(obj.try_into().unwrap()) < 777
+    }).unwrap();
+    assert_eq!(idx, 777);
+    assert_eq!(str.read().unwrap(), 777u32.to_be_bytes());
+
+    assert!(probes <= 2 * 1000u32.ilog2() + 2, "{} probes", probes);
+}
+
+#[test]
+fn test_oversized_frame() {
+    let mut str = StorageObjStr::new(vec![0x7f, 0xff, 0xff, 0xff, b'a', b'b', b'c', b'd', 0]).unwrap();
+    assert_eq!(str.read().unwrap_err().kind(), ErrorKind::InvalidData);
+
+    let mut str = StorageObjStr::new(vec![0xff, 0xff, 0xff, 0xff, b'O', b'S', b'M', 1, 0, 0, 0, 1, 0]).unwrap();
+    assert_eq!(str.read_meta().unwrap_err().kind(), ErrorKind::InvalidData);
+}
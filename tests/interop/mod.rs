@@ -0,0 +1 @@
+pub mod record_obj_str_test;
@@ -0,0 +1,194 @@
+use std::{fs, io::{ErrorKind, SeekFrom}, path::Path};
+
+use objstr::{api::ObjStr as _, interop::{TfRecordObjStr, VarintObjStr}};
+
+fn path(name: &str) -> String {
+    format!(".test/interop/record_obj_str_test/{}.bin", name)
+}
+
+fn setup(name: &str) {
+    let name = path(name);
+    let path = Path::new(
+        name.as_str()
+    );
+    if path.exists() {
+        std::fs::remove_file(path).unwrap();
+    }
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+}
+
+fn tf_record(name: &str) -> TfRecordObjStr {
+    TfRecordObjStr::new(Path::new(path(name).as_str())).unwrap()
+}
+
+fn varint(name: &str) -> VarintObjStr {
+    VarintObjStr::new(Path::new(path(name).as_str())).unwrap()
+}
+
+fn teardown(name: &str) -> Vec<u8> {
+    let bytes = std::fs::read(path(name)).unwrap();
+    std::fs::remove_file(path(name)).unwrap();
+    bytes
+}
+
+#[test]
+fn tf_record_format() {
+    setup("tf_record_format");
+    let mut str = tf_record("tf_record_format");
+    str.append(&[0; 32]).unwrap();
+    drop(str);
+
+    let bytes = teardown("tf_record_format");
+    assert_eq!(bytes.len(), 48);
+    assert_eq!(&bytes[..8], &u64::to_le_bytes(32));
+    assert_eq!(&bytes[12..44], &[0; 32]);
+    // crc32c of 32 zero bytes is 0x8a9136aa (RFC 3720), masked as TFRecord does
+    assert_eq!(&bytes[44..], &u32::to_le_bytes(0x0fd7fffa));
+}
+
+#[test]
+fn tf_record_read_write() {
+    setup("tf_record_read_write");
+    let mut str = tf_record("tf_record_read_write");
+    str.append(b"Hello").unwrap();
+    str.append(b"World").unwrap();
+    str.append(b"!").unwrap();
+    drop(str);
+
+    let mut str = tf_record("tf_record_read_write");
+    assert_eq!(str.count(), 3);
+    assert_eq!(str.read().unwrap(), b"Hello");
+    assert_eq!(str.read().unwrap(), b"World");
+    assert_eq!(str.read().unwrap(), b"!");
+    assert_eq!(str.read().unwrap_err().kind(), ErrorKind::UnexpectedEof);
+
+    str.seek(SeekFrom::End(2)).unwrap();
+    assert_eq!(str.read().unwrap(), b"World");
+    str.seek(SeekFrom::Current(-2)).unwrap();
+    assert_eq!(str.read().unwrap(), b"Hello");
+    assert!(str.seek(SeekFrom::Start(4)).is_err());
+    assert_eq!(str.len(1, 1).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+
+    str.seek(SeekFrom::Start(1)).unwrap();
+    assert_eq!(str.len(1, 1).unwrap(), 5);
+    assert_eq!(str.len(0, 2).unwrap(), 38);
+    str.cut().unwrap();
+    drop(str);
+
+    assert_eq!(teardown("tf_record_read_write").len(), 21);
+}
+
+#[test]
+fn tf_record_detects_corruption() {
+    setup("tf_record_detects_corruption");
+    let mut str = tf_record("tf_record_detects_corruption");
+    str.append(b"Hello").unwrap();
+    drop(str);
+
+    let mut bytes = fs::read(path("tf_record_detects_corruption")).unwrap();
+    bytes[13] = b'a';
+    fs::write(path("tf_record_detects_corruption"), &bytes).unwrap();
+
+    let mut str = tf_record("tf_record_detects_corruption");
+    assert_eq!(str.read().unwrap_err().kind(), ErrorKind::InvalidData);
+    drop(str);
+
+    bytes[0] = 6;
+    fs::write(path("tf_record_detects_corruption"), &bytes).unwrap();
+    assert!(TfRecordObjStr::new(Path::new(path("tf_record_detects_corruption").as_str())).is_err());
+
+    teardown("tf_record_detects_corruption");
+}
+
+#[test]
+fn tf_record_overwrite() {
+    setup("tf_record_overwrite");
+    let mut str = tf_record("tf_record_overwrite");
+    str.append(b"a").unwrap();
+    str.append(b"b").unwrap();
+    str.append(b"c").unwrap();
+
+    str.seek(SeekFrom::Start(1)).unwrap();
+    str.overwrite(&[b"longer".as_slice(), b"x".as_slice()], 1).unwrap();
+    assert_eq!(str.read().unwrap(), b"c");
+    drop(str);
+
+    let mut str = tf_record("tf_record_overwrite");
+    let objs: Vec<Vec<u8>> = (0..4).map(|_| str.read().unwrap()).collect();
+    assert_eq!(objs, vec![b"a".to_vec(), b"longer".to_vec(), b"x".to_vec(), b"c".to_vec()]);
+    drop(str);
+
+    teardown("tf_record_overwrite");
+}
+
+#[test]
+fn tf_record_patch() {
+    setup("tf_record_patch");
+    let mut str = tf_record("tf_record_patch");
+    str.append(b"Hello").unwrap();
+    str.append(b"World").unwrap();
+    drop(str);
+
+    // Damage the second record, patching the first must not touch it
+    let mut bytes = fs::read(path("tf_record_patch")).unwrap();
+    bytes[34] = b'a';
+    fs::write(path("tf_record_patch"), &bytes).unwrap();
+
+    let mut str = tf_record("tf_record_patch");
+    str.patch(1, b"ipp").unwrap();
+    assert_eq!(str.patch(3, b"ooo").unwrap_err().kind(), ErrorKind::InvalidInput);
+    assert_eq!(str.read().unwrap(), b"Hippo");
+    assert_eq!(str.read().unwrap_err().kind(), ErrorKind::InvalidData);
+    drop(str);
+
+    let mut str = tf_record("tf_record_patch");
+    assert_eq!(str.read().unwrap(), b"Hippo");
+    drop(str);
+
+    let patched = teardown("tf_record_patch");
+    assert_eq!(&patched[21..], &bytes[21..]);
+}
+
+#[test]
+fn varint_format() {
+    setup("varint_format");
+    let mut str = varint("varint_format");
+    str.append(b"Hi").unwrap();
+    str.append(&[7; 300]).unwrap();
+    drop(str);
+
+    let mut str = varint("varint_format");
+    assert_eq!(str.count(), 2);
+    str.seek(SeekFrom::Start(1)).unwrap();
+    assert_eq!(str.read().unwrap(), vec![7; 300]);
+    str.seek(SeekFrom::Start(0)).unwrap();
+    assert_eq!(str.read().unwrap(), b"Hi");
+    str.seek(SeekFrom::Start(0)).unwrap();
+    assert_eq!(str.len(1, 1).unwrap(), 2);
+    assert_eq!(str.len(2, 2).unwrap(), 302);
+    str.seek(SeekFrom::Start(1)).unwrap();
+    assert_eq!(str.len(1, 1).unwrap(), 300);
+    assert_eq!(str.len(0, 1).unwrap(), 302);
+    drop(str);
+
+    let bytes = teardown("varint_format");
+    assert_eq!(&bytes[..3], &[2, b'H', b'i']);
+    assert_eq!(&bytes[3..5], &[0xac, 0x02]);
+    assert_eq!(bytes.len(), 305);
+}
+
+#[test]
+fn varint_trim_front() {
+    setup("varint_trim_front");
+    let mut str = varint("varint_trim_front");
+    str.append(b"a").unwrap();
+    str.append(b"b").unwrap();
+    str.append(b"c").unwrap();
+
+    str.trim_front(2).unwrap();
+    str.seek(SeekFrom::Start(0)).unwrap();
+    assert_eq!(str.read().unwrap(), b"c");
+    drop(str);
+
+    assert_eq!(teardown("varint_trim_front"), vec![1, b'c']);
+}
@@ -0,0 +1,188 @@
+use std::{fs, path::Path};
+
+use objstr::{api::ObjStr, file::FileObjStr, kv::KvObjStr};
+use std::io::SeekFrom;
+
+fn setup(name: &str) -> KvObjStr {
+    let name = format!(".test/kv/kv_obj_str_test/{}.bin", name);
+    let path = Path::new(
+        name.as_str()
+    );
+    if path.exists() {
+        std::fs::remove_file(
+            name.as_str()
+        ).unwrap();
+    }
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    KvObjStr::new(FileObjStr::new(path).unwrap()).unwrap()
+}
+
+fn reopen(name: &str) -> FileObjStr {
+    FileObjStr::new(
+        Path::new(format!(".test/kv/kv_obj_str_test/{}.bin", name).as_str())
+    ).unwrap()
+}
+
+fn teardown(name: &str) -> Vec<u8> {
+    let bytes = std::fs::read(
+        format!(".test/kv/kv_obj_str_test/{}.bin", name).as_str()
+    ).unwrap();
+    std::fs::remove_file(
+        format!(".test/kv/kv_obj_str_test/{}.bin", name).as_str()
+    ).unwrap();
+    bytes
+}
+
+#[test]
+fn test_put_get() {
+    let mut kv = setup("test_put_get");
+
+    kv.put(b"key1", b"Hello, world1").unwrap();
+    kv.put(b"key2", b"Hello, world2").unwrap();
+    kv.put(b"key1", b"Hello, world3").unwrap();
+
+    assert_eq!(kv.get(b"key1").unwrap(), Some(b"Hello, world3".to_vec()));
+    assert_eq!(kv.get(b"key2").unwrap(), Some(b"Hello, world2".to_vec()));
+    assert_eq!(kv.get(b"key3").unwrap(), None);
+    assert_eq!(kv.len(), 2);
+
+    let _ = teardown("test_put_get");
+}
+
+#[test]
+fn test_delete() {
+    let mut kv = setup("test_delete");
+
+    kv.put(b"key1", b"Hello, world1").unwrap();
+
+    assert!(kv.delete(b"key1").unwrap());
+    assert!(!kv.delete(b"key1").unwrap());
+    assert_eq!(kv.get(b"key1").unwrap(), None);
+
+    drop(kv);
+
+    let kv = KvObjStr::new(reopen("test_delete")).unwrap();
+
+    assert!(!kv.contains_key(b"key1"));
+
+    let _ = teardown("test_delete");
+}
+
+#[test]
+fn test_reopen() {
+    let mut kv = setup("test_reopen");
+
+    kv.put(b"key1", b"Hello, world1").unwrap();
+    kv.put(b"key1", b"Hello, world2").unwrap();
+
+    drop(kv);
+
+    let mut kv = KvObjStr::new(reopen("test_reopen")).unwrap();
+
+    assert_eq!(kv.get(b"key1").unwrap(), Some(b"Hello, world2".to_vec()));
+
+    let _ = teardown("test_reopen");
+}
+
+#[test]
+fn test_with_index() {
+    let mut kv = setup("test_with_index");
+    let index = Path::new(".test/kv/kv_obj_str_test/test_with_index.idx");
+
+    kv.put(b"key1", b"Hello, world1").unwrap();
+    kv.save_index(index).unwrap();
+    kv.put(b"key2", b"Hello, world2").unwrap();
+
+    drop(kv);
+
+    let mut kv = KvObjStr::with_index(reopen("test_with_index"), index).unwrap();
+
+    assert_eq!(kv.get(b"key1").unwrap(), Some(b"Hello, world1".to_vec()));
+    assert_eq!(kv.get(b"key2").unwrap(), Some(b"Hello, world2".to_vec()));
+
+    fs::remove_file(index).unwrap();
+    let _ = teardown("test_with_index");
+}
+
+#[test]
+fn test_with_index_stale() {
+    let mut kv = setup("test_with_index_stale");
+    let index = Path::new(".test/kv/kv_obj_str_test/test_with_index_stale.idx");
+
+    kv.put(b"key1", b"Hello, world1").unwrap();
+    kv.put(b"key2", b"Hello, world2").unwrap();
+    kv.save_index(index).unwrap();
+
+    let mut str = kv.into_inner();
+    str.seek(SeekFrom::Start(1)).unwrap();
+    str.cut().unwrap();
+
+    let mut kv = KvObjStr::new(str).unwrap();
+    kv.put(b"key3", b"Hello, world3").unwrap();
+
+    drop(kv);
+
+    let mut kv = KvObjStr::with_index(reopen("test_with_index_stale"), index).unwrap();
+
+    assert_eq!(kv.get(b"key2").unwrap(), None);
+    assert_eq!(kv.get(b"key3").unwrap(), Some(b"Hello, world3".to_vec()));
+
+    fs::remove_file(index).unwrap();
+    let _ = teardown("test_with_index_stale");
+}
+
+#[test]
+fn test_with_index_damaged() {
+    let mut kv = setup("test_with_index_damaged");
+    let index = Path::new(".test/kv/kv_obj_str_test/test_with_index_damaged.idx");
+
+    kv.put(b"key1", b"Hello, world1").unwrap();
+    kv.put(b"key2", b"Hello, world2").unwrap();
+    kv.save_index(index).unwrap();
+    assert!(!Path::new(".test/kv/kv_obj_str_test/test_with_index_damaged.idx.tmp").exists());
+
+    let saved = fs::read(index).unwrap();
+    drop(kv);
+
+    for damage in [saved.len() - 16, saved.len() - 1] {
+        let mut bytes = saved.clone();
+        bytes.truncate(damage);
+        fs::write(index, bytes).unwrap();
+
+        let mut kv = KvObjStr::with_index(reopen("test_with_index_damaged"), index).unwrap();
+
+        assert_eq!(kv.get(b"key1").unwrap(), Some(b"Hello, world1".to_vec()));
+        assert_eq!(kv.get(b"key2").unwrap(), Some(b"Hello, world2".to_vec()));
+    }
+
+    fs::remove_file(index).unwrap();
+    let _ = teardown("test_with_index_damaged");
+}
+
+#[test]
+fn test_compact() {
+    let mut kv = setup("test_compact");
+
+    kv.put(b"k1", b"Hello, world1").unwrap();
+    kv.put(b"k2", b"Hello, world2").unwrap();
+    kv.put(b"k1", b"Hello, world3").unwrap();
+    kv.delete(b"k2").unwrap();
+
+    kv.compact().unwrap();
+
+    assert_eq!(kv.get(b"k1").unwrap(), Some(b"Hello, world3".to_vec()));
+    assert_eq!(kv.get(b"k2").unwrap(), None);
+    assert!(!Path::new(".test/kv/kv_obj_str_test/test_compact.bin.compact").exists());
+
+    kv.put(b"k2", b"Hello, world4").unwrap();
+    assert_eq!(kv.get(b"k2").unwrap(), Some(b"Hello, world4".to_vec()));
+
+    kv.delete(b"k2").unwrap();
+    kv.compact().unwrap();
+
+    drop(kv);
+
+    let bytes = teardown("test_compact");
+
+    assert_eq!(bytes, b"\x00\x00\x00\x14\x00\x00\x00\x00\x02k1Hello, world3\x00\x00\x00\x14\x00".to_vec());
+}
@@ -0,0 +1,2 @@
+
+pub mod kv_obj_str_test;
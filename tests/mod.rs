@@ -1,3 +1,20 @@
+#[cfg(all(test, feature = "std"))]
+mod convert;
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
+mod diff;
+
+#[cfg(all(test, feature = "std"))]
 mod file;
+
+#[cfg(test)]
+mod frame;
+
+#[cfg(all(test, feature = "std"))]
+mod interop;
+
+#[cfg(all(test, feature = "std"))]
+mod kv;
+
+#[cfg(all(test, feature = "std"))]
+mod net;
@@ -0,0 +1,138 @@
+use std::{fs, io::{Read as _, Write as _}, net::{TcpListener, TcpStream}, path::{Path, PathBuf}, thread};
+
+use objstr::{api::ObjStr as _, file::FileObjStr, net::Gateway};
+
+fn setup(name: &str) -> (String, PathBuf) {
+    let dir = PathBuf::from(format!(".test/net/gateway_test/{}", name));
+    if dir.exists() {
+        fs::remove_dir_all(&dir).unwrap();
+    }
+    fs::create_dir_all(&dir).unwrap();
+
+    let gateway = Gateway::new(&dir);
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    thread::spawn(move || gateway.serve(listener));
+    (addr, dir)
+}
+
+fn teardown(dir: &Path) {
+    fs::remove_dir_all(dir).unwrap();
+}
+
+/// Sends a raw request and returns the status, the head and the body of the response.
+fn request(addr: &str, head: &str, body: &[u8]) -> (u16, String, Vec<u8>) {
+    let mut stream = TcpStream::connect(addr).unwrap();
+    write!(stream, "{}\r\nContent-Length: {}\r\n\r\n", head, body.len()).unwrap();
+    stream.write_all(body).unwrap();
+
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).unwrap();
+
+    let split = response.windows(4).position(|w| w == b"\r\n\r\n").unwrap();
+    let head = String::from_utf8(response[..split].to_vec()).unwrap();
+    let status = head[9..12].parse().unwrap();
+    (status, head, response[split + 4..].to_vec())
+}
+
+#[test]
+fn test_append_get() {
+    let (addr, dir) = setup("test_append_get");
+
+    let (status, head, body) = request(&addr, "POST /streams/logs/objects HTTP/1.1", b"Hello");
+    assert_eq!(status, 201);
+    assert!(head.contains("Location: /streams/logs/objects/0"));
+    assert_eq!(body, b"0\n");
+
+    let (status, _, _) = request(&addr, "POST /streams/logs/objects HTTP/1.1\r\nContent-Type: text/plain", b"World");
+    assert_eq!(status, 201);
+
+    let (status, head, body) = request(&addr, "GET /streams/logs/objects/0 HTTP/1.1", b"");
+    assert_eq!(status, 200);
+    assert!(head.contains("Content-Type: application/octet-stream"));
+    assert_eq!(body, b"Hello");
+
+    let (status, head, body) = request(&addr, "GET /streams/logs/objects/1 HTTP/1.1", b"");
+    assert_eq!(status, 200);
+    assert!(head.contains("Content-Type: text/plain"));
+    assert_eq!(body, b"World");
+
+    assert_eq!(request(&addr, "GET /streams/logs/objects/2 HTTP/1.1", b"").0, 404);
+    assert_eq!(request(&addr, "GET /streams/missing/objects/0 HTTP/1.1", b"").0, 404);
+    assert_eq!(request(&addr, "GET /streams/..hidden HTTP/1.1", b"").0, 400);
+    assert_eq!(request(&addr, "PUT /streams/logs/objects HTTP/1.1", b"").0, 405);
+
+    let mut str = FileObjStr::new(&dir.join("logs")).unwrap();
+    assert_eq!(str.read().unwrap(), b"Hello");
+    assert_eq!(str.read_meta().unwrap().unwrap().content_type.as_deref(), Some("text/plain"));
+
+    teardown(&dir);
+}
+
+#[test]
+fn test_range_requests() {
+    let (addr, dir) = setup("test_range_requests");
+    request(&addr, "POST /streams/logs/objects HTTP/1.1", b"Hello World");
+    request(&addr, "POST /streams/logs/objects HTTP/1.1\r\nContent-Type: text/plain", b"Hello World");
+
+    for index in 0..2 {
+        let (status, head, body) = request(&addr, &format!("GET /streams/logs/objects/{} HTTP/1.1\r\nRange: bytes=6-", index), b"");
+        assert_eq!(status, 206);
+        assert!(head.contains("Content-Range: bytes 6-10/11"));
+        assert_eq!(body, b"World");
+
+        let (status, head, body) = request(&addr, &format!("GET /streams/logs/objects/{} HTTP/1.1\r\nRange: bytes=0-3", index), b"");
+        assert_eq!(status, 206);
+        assert!(head.contains("Content-Range: bytes 0-3/11"));
+        assert_eq!(body, b"Hell");
+
+        let (_, _, body) = request(&addr, &format!("GET /streams/logs/objects/{} HTTP/1.1\r\nRange: bytes=-2", index), b"");
+        assert_eq!(body, b"ld");
+
+        let (status, head, _) = request(&addr, &format!("GET /streams/logs/objects/{} HTTP/1.1\r\nRange: bytes=11-", index), b"");
+        assert_eq!(status, 416);
+        assert!(head.contains("Content-Range: bytes */11"));
+    }
+
+    teardown(&dir);
+}
+
+#[test]
+fn test_list_and_cut() {
+    let (addr, dir) = setup("test_list_and_cut");
+    for data in [b"a".as_slice(), b"b", b"c"] {
+        request(&addr, "POST /streams/logs/objects HTTP/1.1", data);
+    }
+
+    let (status, head, body) = request(&addr, "GET /streams/logs?from=1&limit=1 HTTP/1.1", b"");
+    assert_eq!(status, 200);
+    assert!(head.contains("Content-Type: application/x-ndjson"));
+    assert_eq!(body, b"{\"index\":1,\"length\":1,\"encoding\":\"utf8\",\"payload\":\"b\"}\n");
+
+    let (_, _, body) = request(&addr, "GET /streams/logs HTTP/1.1", b"");
+    assert_eq!(String::from_utf8(body).unwrap().lines().count(), 3);
+
+    assert_eq!(request(&addr, "DELETE /streams/logs/objects/1.. HTTP/1.1", b"").0, 204);
+    assert_eq!(request(&addr, "DELETE /streams/logs/objects/1 HTTP/1.1", b"").0, 400);
+    assert_eq!(request(&addr, "DELETE /streams/logs/objects/5.. HTTP/1.1", b"").0, 404);
+
+    let (_, _, body) = request(&addr, "GET /streams/logs?from=0 HTTP/1.1", b"");
+    assert_eq!(body, b"{\"index\":0,\"length\":1,\"encoding\":\"utf8\",\"payload\":\"a\"}\n");
+
+    teardown(&dir);
+}
+
+#[test]
+fn test_oversized_body() {
+    let (addr, dir) = setup("test_oversized_body");
+
+    let mut stream = TcpStream::connect(&addr).unwrap();
+    write!(stream, "POST /streams/logs/objects HTTP/1.1\r\nContent-Length: 1000000000000\r\n\r\n").unwrap();
+
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).unwrap();
+    assert!(response.starts_with(b"HTTP/1.1 413 "));
+    assert!(!dir.join("logs").exists());
+
+    teardown(&dir);
+}
@@ -0,0 +1,3 @@
+pub mod remote_obj_str_test;
+#[cfg(feature = "http")]
+pub mod gateway_test;
@@ -0,0 +1,133 @@
+use std::{fs, io::{ErrorKind, Read as _, SeekFrom, Write as _}, net::{TcpListener, TcpStream}, path::Path, thread, time::Duration};
+
+use objstr::{api::ObjStr as _, file::FileObjStr, net::{RemoteObjStr, Server}};
+
+fn setup(name: &str) -> String {
+    let name = format!(".test/net/remote_obj_str_test/{}.bin", name);
+    let path = Path::new(
+        name.as_str()
+    );
+    if path.exists() {
+        std::fs::remove_file(path).unwrap();
+    }
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+
+    let server = Server::new(FileObjStr::new(path).unwrap());
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    thread::spawn(move || server.serve(listener));
+    addr
+}
+
+fn teardown(name: &str) -> Vec<u8> {
+    let path = format!(".test/net/remote_obj_str_test/{}.bin", name);
+    let bytes = std::fs::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    bytes
+}
+
+#[test]
+fn test_append_read() {
+    let addr = setup("test_append_read");
+    let mut str = RemoteObjStr::connect(&addr).unwrap();
+
+    str.append(b"Hello").unwrap();
+    str.append(b"World").unwrap();
+    assert_eq!(str.count().unwrap(), 2);
+
+    assert_eq!(str.read().unwrap(), b"Hello");
+    assert_eq!(str.read().unwrap(), b"World");
+    assert_eq!(str.read().unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    drop(str);
+
+    assert_eq!(
+        teardown("test_append_read"),
+        vec![0, 0, 0, 5, b'H', b'e', b'l', b'l', b'o', 0, 0, 0, 5, 0, 0, 0, 5, b'W', b'o', b'r', b'l', b'd', 0, 0, 0, 5, 0]
+    );
+}
+
+#[test]
+fn test_seek_len_overwrite_cut() {
+    let addr = setup("test_seek_len_overwrite_cut");
+    let mut str = RemoteObjStr::connect(&addr).unwrap();
+
+    str.append(b"a").unwrap();
+    str.append(b"b").unwrap();
+    str.append(b"c").unwrap();
+
+    str.seek(SeekFrom::End(2)).unwrap();
+    assert_eq!(str.len(1, 1).unwrap(), 1);
+    assert_eq!(str.len(0, 2).unwrap(), 18);
+    str.overwrite(&[b"x".as_slice()], 1).unwrap();
+    assert_eq!(str.read().unwrap(), b"c");
+
+    str.seek(SeekFrom::Current(-2)).unwrap();
+    assert_eq!(str.read().unwrap(), b"x");
+    assert_eq!(str.seek(SeekFrom::Start(4)).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    assert!(str.overwrite(&[b"too long".as_slice()], 1).is_err());
+
+    str.seek(SeekFrom::Start(1)).unwrap();
+    str.cut().unwrap();
+    assert_eq!(str.count().unwrap(), 1);
+    drop(str);
+
+    assert_eq!(teardown("test_seek_len_overwrite_cut"), vec![0, 0, 0, 1, b'a', 0, 0, 0, 1, 0]);
+}
+
+#[test]
+fn test_clients_have_own_cursors() {
+    let addr = setup("test_clients_have_own_cursors");
+    let mut first = RemoteObjStr::connect(&addr).unwrap();
+    let mut second = RemoteObjStr::connect(&addr).unwrap();
+
+    first.append(b"Hello").unwrap();
+    second.append(b"World").unwrap();
+
+    assert_eq!(first.read().unwrap(), b"Hello");
+    assert_eq!(second.read().unwrap(), b"Hello");
+    assert_eq!(first.read().unwrap(), b"World");
+
+    second.cut().unwrap();
+    assert_eq!(first.count().unwrap(), 1);
+    assert_eq!(first.read().unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    first.append(b"Again").unwrap();
+    assert_eq!(second.read().unwrap(), b"Again");
+    drop((first, second));
+
+    teardown("test_clients_have_own_cursors");
+}
+
+#[test]
+fn test_cursor_survives_rewrite() {
+    let addr = setup("test_cursor_survives_rewrite");
+    let mut first = RemoteObjStr::connect(&addr).unwrap();
+    let mut second = RemoteObjStr::connect(&addr).unwrap();
+
+    first.append(b"a").unwrap();
+    first.append(b"b").unwrap();
+    assert_eq!(first.read().unwrap(), b"a");
+
+    second.cut().unwrap();
+    second.append(b"Hello").unwrap();
+    second.append(b"World").unwrap();
+
+    assert_eq!(first.read().unwrap(), b"World");
+    drop((first, second));
+
+    teardown("test_cursor_survives_rewrite");
+}
+
+#[test]
+fn test_oversized_message() {
+    let addr = setup("test_oversized_message");
+    let mut stream = TcpStream::connect(&addr).unwrap();
+    stream.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+
+    stream.write_all(&[7, 0xff, 0xff, 0xff, 0xff]).unwrap();
+
+    let mut buf = [0; 1];
+    assert_eq!(stream.read(&mut buf).unwrap(), 0);
+    drop(stream);
+
+    teardown("test_oversized_message");
+}